@@ -0,0 +1,20 @@
+use spinoff::{Color, Spinner, Spinners};
+use std::{thread::sleep, time::Duration};
+
+fn main() {
+    let sp = Spinner::new(Spinners::Dots, "Building workspace...", Color::Blue);
+    for krate in ["core", "macros", "cli"] {
+        let child = sp.child(format!("Compiling {}...", krate));
+        for unit in 1..=2 {
+            let codegen = child.child(format!("Codegen unit {}...", unit));
+            sleep(Duration::from_millis(600));
+            if krate == "cli" && unit == 2 {
+                codegen.fail(format!("Codegen unit {} ran out of memory", unit));
+            } else {
+                codegen.success(format!("Codegen unit {}", unit));
+            }
+        }
+        child.success(format!("Compiled {}", krate));
+    }
+    sp.success("Built workspace");
+}
@@ -0,0 +1,23 @@
+use spinoff::{Color, Spinner, Spinners, Unit};
+use std::{thread, time::Duration};
+
+fn main() {
+    let mut sp = Spinner::new(Spinners::Dots, "Indexing", Color::Blue);
+    sp.set_unit(Unit::items("files"));
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let counter = sp.counter();
+            thread::spawn(move || {
+                for _ in 0..2_000 {
+                    thread::sleep(Duration::from_millis(1));
+                    counter.inc(1);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    let indexed = sp.position();
+    sp.success(format!("Indexed {} files", indexed));
+}
@@ -0,0 +1,15 @@
+use spinoff::{Color, Spinner, Spinners};
+use std::{thread::sleep, time::Duration};
+
+fn main() {
+    let mut sp = Spinner::new(Spinners::Dots, "Listing files...", Color::Blue);
+    sleep(Duration::from_secs(2));
+    let files = 40;
+    sp.update_text(format!("Copying {} files", files));
+    sp.set_length(files);
+    for _ in 0..files {
+        sleep(Duration::from_millis(100));
+        sp.inc(1);
+    }
+    sp.success("Copied all files");
+}
@@ -0,0 +1,21 @@
+use spinoff::{Spinner, Spinners};
+use std::io::{stdin, stdout, Write};
+use std::{thread::sleep, time::Duration};
+
+fn main() {
+    let sp = Spinner::new(Spinners::Dots, "Installing...", None);
+    sleep(Duration::from_secs(2));
+    let answer = sp.suspend(|| {
+        print!("Overwrite the existing config? [y/N] ");
+        stdout().flush().unwrap();
+        let mut answer = String::new();
+        stdin().read_line(&mut answer).unwrap();
+        answer.trim().eq_ignore_ascii_case("y")
+    });
+    sleep(Duration::from_secs(2));
+    if answer {
+        sp.success("Installed with a fresh config!");
+    } else {
+        sp.success("Installed, kept the existing config.");
+    }
+}
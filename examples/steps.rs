@@ -0,0 +1,14 @@
+use spinoff::{Color, Spinner, Spinners};
+use std::{thread::sleep, time::Duration};
+
+fn main() {
+    let mut sp = Spinner::new(Spinners::Dots, "Resolving dependencies...", Color::Blue);
+    sleep(Duration::from_secs(1));
+    sp.step("Downloading...");
+    sleep(Duration::from_secs(2));
+    sp.step_warned("Checking the lockfile...");
+    sleep(Duration::from_secs(1));
+    sp.step("Compiling...");
+    sleep(Duration::from_secs(2));
+    sp.success("Done!");
+}
@@ -0,0 +1,25 @@
+use spinoff::{Color, Outcome, Spinners, TaskList};
+use std::{thread::sleep, time::Duration};
+
+fn main() {
+    let summary = TaskList::new(Spinners::Dots, Color::Blue)
+        .concurrency(2)
+        .exit_on_error(false)
+        .task("Fetch sources", || sleep(Duration::from_secs(2)))
+        .task("Install dependencies", || sleep(Duration::from_secs(3)))
+        .task("Run migrations", || -> Result<(), String> {
+            sleep(Duration::from_secs(1));
+            Err("relation \"users\" already exists".into())
+        })
+        .task("Warm the cache", || {
+            sleep(Duration::from_secs(1));
+            Outcome::Warn("cache server unreachable, continuing without it".into())
+        })
+        .task("Publish docs", || {
+            Outcome::Skip("not on the main branch".into())
+        })
+        .run();
+    for task in &summary.tasks {
+        println!("{}: {:?} in {:?}", task.name, task.outcome, task.duration);
+    }
+}
@@ -0,0 +1,137 @@
+use clap::{Args, ValueEnum};
+use spinoff::{set_color_mode, Color, ColorMode, Spinner, Spinners, Streams, Theme};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+/// How the spinner looks, and where it's printed.
+#[derive(Debug, Args)]
+pub struct SpinnerArgs {
+    /// The spinner to show, by name (`dots`, `line`, `bouncingBar`...).
+    #[arg(short, long, default_value = "dots", value_parser = parse_spinner)]
+    pub spinner: Spinners,
+    /// The color of the spinner: a name like `blue` or `bright-red`, a 256-color index or `#rrggbb`.
+    #[arg(short, long, value_parser = parse_color)]
+    pub color: Option<Color>,
+    /// When to use colors.
+    #[arg(long, value_enum, default_value = "auto")]
+    pub color_mode: WhenArg,
+    /// The stream the spinner is printed on.
+    #[arg(long, value_enum, default_value = "stderr")]
+    pub stream: StreamArg,
+    /// The symbols in front of the finish line.
+    #[arg(long, value_enum, default_value = "unicode")]
+    pub symbols: SymbolsArg,
+}
+
+impl SpinnerArgs {
+    /// Start a spinner that looks the way the flags say.
+    pub fn start(&self, text: &str) -> Spinner {
+        set_color_mode(self.color_mode.into());
+        let mut spinner =
+            Spinner::new_with_stream(self.spinner, text, self.color, self.stream.into());
+        spinner.set_theme(self.symbols.into());
+        spinner
+    }
+}
+
+/// Internal function for looking up a spinner by name, ignoring case: `dots`, `Dots2`, `bouncingBar`...
+pub fn parse_spinner(name: &str) -> Result<Spinners, String> {
+    Spinners::iter()
+        .find(|spinner| spinner.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("no spinner named `{}`", name))
+}
+
+/// Internal function for parsing a color: a name like `blue` or `bright-red`, a 256-color index or `#rrggbb`.
+pub fn parse_color(color: &str) -> Result<Color, String> {
+    if let Some(hex) = color.strip_prefix('#') {
+        let channel = |range| {
+            hex.get(range)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+        return match (hex.len(), channel(0..2), channel(2..4), channel(4..6)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::TrueColor { r, g, b }),
+            _ => Err(format!("`{}` isn't a `#rrggbb` color", color)),
+        };
+    }
+    if let Ok(index) = color.parse::<u8>() {
+        return Ok(Color::Ansi256(index));
+    }
+    let color = match color.to_ascii_lowercase().replace('_', "-").as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "bright-black" => Color::BrightBlack,
+        "bright-red" => Color::BrightRed,
+        "bright-green" => Color::BrightGreen,
+        "bright-yellow" => Color::BrightYellow,
+        "bright-blue" => Color::BrightBlue,
+        "bright-magenta" => Color::BrightMagenta,
+        "bright-cyan" => Color::BrightCyan,
+        "bright-white" => Color::BrightWhite,
+        _ => return Err(format!("no color named `{}`", color)),
+    };
+    Ok(color)
+}
+
+/// Internal function for parsing a timeout in seconds, like `30` or `1.5`.
+pub fn parse_timeout(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("`{}` isn't a number of seconds", seconds))
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum StreamArg {
+    Stdout,
+    Stderr,
+}
+
+impl From<StreamArg> for Streams {
+    fn from(stream: StreamArg) -> Self {
+        match stream {
+            StreamArg::Stdout => Streams::Stdout,
+            StreamArg::Stderr => Streams::Stderr,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum WhenArg {
+    Always,
+    Auto,
+    Never,
+}
+
+impl From<WhenArg> for ColorMode {
+    fn from(when: WhenArg) -> Self {
+        match when {
+            WhenArg::Always => ColorMode::Always,
+            WhenArg::Auto => ColorMode::Auto,
+            WhenArg::Never => ColorMode::Never,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum SymbolsArg {
+    Unicode,
+    Ascii,
+    Emoji,
+}
+
+impl From<SymbolsArg> for Theme {
+    fn from(symbols: SymbolsArg) -> Self {
+        match symbols {
+            SymbolsArg::Unicode => Theme::unicode(),
+            SymbolsArg::Ascii => Theme::ascii(),
+            SymbolsArg::Emoji => Theme::emoji(),
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! One spinner across many commands of a shell script:
+//!
+//! ```text
+//! spinoff start --text "Setting up"
+//! spinoff update "Installing deps"
+//! spinoff step "Configuring"
+//! spinoff succeed "Done"
+//! ```
+//!
+//! `start` runs `spinoff` again in the background, which owns the spinner and listens on a Unix socket.
+//! The other commands send it one message each, and wait for it to be applied.
+//! The socket is in `$XDG_RUNTIME_DIR`, or else in a directory under the temporary one that only the user can get into.
+//! The spinner fails on its own if the script exits without finishing it.
+use crate::args::{SpinnerArgs, StreamArg};
+use clap::{Args, Subcommand};
+use std::ffi::OsString;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long `start` waits for the background spinner to be listening.
+const START_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often the background spinner checks on the script while nothing is sent to it.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long the background spinner waits for a message once a command is connected.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The name of the hidden subcommand `start` runs in the background.
+const SERVE: &str = "__serve";
+
+#[derive(Debug, Subcommand)]
+pub enum Action {
+    /// Start a spinner in the background, for the commands below to drive.
+    Start(StartArgs),
+    /// Change the text of the running spinner.
+    Update {
+        /// The new text.
+        text: String,
+        #[command(flatten)]
+        socket: SocketArg,
+    },
+    /// Mark the current step of the running spinner as done, and go on to the next one.
+    Step {
+        /// The text of the next step.
+        text: String,
+        #[command(flatten)]
+        socket: SocketArg,
+    },
+    /// Finish the running spinner with a success line.
+    Succeed {
+        /// The text of the finish line. Defaults to the current text.
+        text: Option<String>,
+        #[command(flatten)]
+        socket: SocketArg,
+    },
+    /// Finish the running spinner with a failure line.
+    Fail {
+        /// The text of the finish line. Defaults to the current text.
+        text: Option<String>,
+        #[command(flatten)]
+        socket: SocketArg,
+    },
+    /// Finish the running spinner with a warning line.
+    Warn {
+        /// The text of the finish line. Defaults to the current text.
+        text: Option<String>,
+        #[command(flatten)]
+        socket: SocketArg,
+    },
+    /// Finish the running spinner with an info line.
+    Info {
+        /// The text of the finish line. Defaults to the current text.
+        text: Option<String>,
+        #[command(flatten)]
+        socket: SocketArg,
+    },
+    /// Own the spinner in the background. Run by `start`.
+    #[command(name = SERVE, hide = true)]
+    Serve {
+        /// The process the spinner belongs to.
+        #[arg(long)]
+        owner: u32,
+        #[command(flatten)]
+        start: StartArgs,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct StartArgs {
+    #[command(flatten)]
+    spinner: SpinnerArgs,
+    /// The text next to the spinner.
+    #[arg(short, long)]
+    text: String,
+    #[command(flatten)]
+    socket: SocketArg,
+}
+
+#[derive(Debug, Args)]
+pub struct SocketArg {
+    /// The socket the running spinner listens on. Defaults to one for the shell the command is run from.
+    #[arg(long, env = "SPINOFF_SOCKET")]
+    socket: Option<PathBuf>,
+}
+
+impl SocketArg {
+    /// The socket of the spinner that belongs to the process `owner`.
+    fn path(&self, owner: u32) -> Result<PathBuf, String> {
+        if let Some(path) = &self.socket {
+            return Ok(path.clone());
+        }
+        // An empty or relative one would put the socket wherever the command happens to be run from.
+        let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute());
+        let dir = match runtime_dir {
+            Some(dir) => dir,
+            None => private_dir()?,
+        };
+        Ok(dir.join(format!("spinoff-{}.sock", owner)))
+    }
+}
+
+/// A directory in the temporary directory only the current user can get into, for when there's no runtime directory.
+fn private_dir() -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(format!("spinoff-{}", uid()));
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Ok(()) => return Ok(dir),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(err) => return Err(format!("failed to create {}: {}", dir.display(), err)),
+    }
+    // Anyone can create it first in a shared temporary directory, so it has to be checked.
+    let metadata = std::fs::symlink_metadata(&dir)
+        .map_err(|err| format!("failed to check {}: {}", dir.display(), err))?;
+    if !metadata.is_dir() || metadata.uid() != uid() || metadata.mode() & 0o077 != 0 {
+        return Err(format!(
+            "{} isn't a directory only you can access, set XDG_RUNTIME_DIR or --socket",
+            dir.display()
+        ));
+    }
+    Ok(dir)
+}
+
+/// Remove the socket a spinner that was killed left behind, if it's ours to remove.
+fn remove_stale(path: &Path) -> Result<(), String> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(format!("failed to check {}: {}", path.display(), err)),
+    };
+    if !metadata.file_type().is_socket() || metadata.uid() != uid() {
+        return Err(format!(
+            "{} is in the way, and isn't a socket of yours to remove",
+            path.display()
+        ));
+    }
+    std::fs::remove_file(path)
+        .map_err(|err| format!("failed to remove {}: {}", path.display(), err))
+}
+
+/// The user `spinoff` runs as.
+fn uid() -> u32 {
+    // SAFETY: `getuid` always succeeds and has no memory safety requirements.
+    unsafe { libc::getuid() }
+}
+
+pub fn run(action: Action) -> ExitCode {
+    // Every command of a script has the script as its parent, which is what ties them to the same spinner.
+    let owner = std::os::unix::process::parent_id();
+    let result = match action {
+        Action::Start(start) => spawn(owner, &start),
+        Action::Serve { owner, start } => serve(owner, &start),
+        Action::Update { text, socket } => send(&socket, owner, "update", Some(&text)),
+        Action::Step { text, socket } => send(&socket, owner, "step", Some(&text)),
+        Action::Succeed { text, socket } => send(&socket, owner, "succeed", text.as_deref()),
+        Action::Fail { text, socket } => send(&socket, owner, "fail", text.as_deref()),
+        Action::Warn { text, socket } => send(&socket, owner, "warn", text.as_deref()),
+        Action::Info { text, socket } => send(&socket, owner, "info", text.as_deref()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("spinoff: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run `spinoff` again in the background to own the spinner, and wait until it's listening.
+fn spawn(owner: u32, start: &StartArgs) -> Result<(), String> {
+    let path = start.socket.path(owner)?;
+    if UnixStream::connect(&path).is_ok() {
+        return Err(format!(
+            "a spinner is already running on {}",
+            path.display()
+        ));
+    }
+    remove_stale(&path)?;
+    let exe = std::env::current_exe().map_err(|err| format!("failed to find spinoff: {}", err))?;
+    // Everything after `start` is passed on as is, so the flags don't have to be turned back into text.
+    let args: Vec<OsString> = std::env::args_os().skip(2).collect();
+    // Keeping stdout open would hang `$(spinoff start ...)` until the spinner is finished.
+    let stdout = match start.spinner.stream {
+        StreamArg::Stdout => Stdio::inherit(),
+        StreamArg::Stderr => Stdio::null(),
+    };
+    let mut child = Command::new(exe)
+        .arg(SERVE)
+        .arg("--owner")
+        .arg(owner.to_string())
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .spawn()
+        .map_err(|err| format!("failed to start the spinner: {}", err))?;
+    let deadline = Instant::now() + START_TIMEOUT;
+    while !path.exists() {
+        // It has already said why.
+        if let Ok(Some(_)) = child.try_wait() {
+            return Err("failed to start the spinner".into());
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            return Err(format!("timed out waiting for {}", path.display()));
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    Ok(())
+}
+
+/// Own the spinner until it's finished, until the script it belongs to exits, or until it's interrupted.
+///
+/// The signals are handled here instead of by `spinoff::install_signal_handlers`, so the socket is removed on the way out.
+fn serve(owner: u32, start: &StartArgs) -> Result<(), String> {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM, SIGHUP] {
+        signal_hook::flag::register(signal, Arc::clone(&interrupted))
+            .map_err(|err| format!("failed to install the signal handlers: {}", err))?;
+    }
+    let path = start.socket.path(owner)?;
+    let listener = UnixListener::bind(&path)
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+        .map_err(|err| format!("failed to listen on {}: {}", path.display(), err))?;
+    let mut text = start.text.clone();
+    let mut spinner = start.spinner.start(&text);
+    loop {
+        if interrupted.load(Ordering::Relaxed) {
+            spinner.fail(format!("{} (interrupted)", text));
+            break;
+        }
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                if !is_alive(owner) {
+                    // Most likely stopped by `set -e`, so the step it was on is what failed.
+                    spinner.fail(text);
+                    break;
+                }
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(err) => {
+                spinner.fail(format!("{} (failed to accept a command: {})", text, err));
+                break;
+            }
+        };
+        let Some(message) = receive(&mut stream) else {
+            continue;
+        };
+        let (verb, arg) = match message.split_once('\t') {
+            Some((verb, arg)) => (verb, Some(arg.to_owned())),
+            None => (message.as_str(), None),
+        };
+        let finish = arg.clone().unwrap_or_else(|| text.clone());
+        match verb {
+            "update" => {
+                text = finish;
+                spinner.update_text(text.clone());
+            }
+            "step" => {
+                text = finish;
+                spinner.step(text.clone());
+            }
+            "succeed" | "fail" | "warn" | "info" => {
+                match verb {
+                    "succeed" => spinner.success(finish),
+                    "fail" => spinner.fail(finish),
+                    "warn" => spinner.warn(finish),
+                    _ => spinner.info(finish),
+                };
+                // Only answer once the finish line is out, so the script's next output comes after it.
+                let _ = stream.write_all(b"ok");
+                break;
+            }
+            _ => {
+                let _ = write!(stream, "unknown command `{}`", verb);
+                continue;
+            }
+        }
+        let _ = stream.write_all(b"ok");
+    }
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+/// Read a whole message from a command. Returns `None` if it didn't send one in time.
+fn receive(stream: &mut UnixStream) -> Option<String> {
+    // Accepted streams are nonblocking on some platforms when the listener is.
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(READ_TIMEOUT)).ok()?;
+    let mut message = String::new();
+    stream.read_to_string(&mut message).ok()?;
+    Some(message)
+}
+
+/// Send a message to the running spinner, and wait for it to be applied.
+fn send(socket: &SocketArg, owner: u32, verb: &str, text: Option<&str>) -> Result<(), String> {
+    let path = socket.path(owner)?;
+    let mut stream = UnixStream::connect(&path).map_err(|err| {
+        format!(
+            "no spinner is running on {} ({}), start one with `spinoff start`",
+            path.display(),
+            err
+        )
+    })?;
+    let message = match text {
+        Some(text) => format!("{}\t{}", verb, text),
+        None => verb.to_owned(),
+    };
+    let mut reply = String::new();
+    stream
+        .write_all(message.as_bytes())
+        .and_then(|()| stream.shutdown(Shutdown::Write))
+        .and_then(|()| stream.read_to_string(&mut reply).map(|_| ()))
+        .map_err(|err| format!("failed to talk to the spinner: {}", err))?;
+    match reply.as_str() {
+        "ok" => Ok(()),
+        _ => Err(reply),
+    }
+}
+
+/// Whether the process `pid` is still running.
+fn is_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks whether the process exists, nothing is sent.
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
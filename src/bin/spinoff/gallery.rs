@@ -0,0 +1,423 @@
+//! `spinoff gallery`: every spinner animated at once, to pick one and get the code for it.
+use crate::args::parse_color;
+use clap::{Args, ValueEnum};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, terminal};
+use spinoff::{Color, Spinners, Style};
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
+use unicode_width::UnicodeWidthStr;
+
+/// How often the grid is redrawn.
+const FRAME_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The colors `c` goes through.
+const COLORS: [Option<Color>; 9] = [
+    None,
+    Some(Color::Red),
+    Some(Color::Green),
+    Some(Color::Yellow),
+    Some(Color::Blue),
+    Some(Color::Magenta),
+    Some(Color::Cyan),
+    Some(Color::White),
+    Some(Color::BrightBlack),
+];
+
+/// Lines taken by the header and the footer around the grid.
+const CHROME_HEIGHT: usize = 6;
+
+/// Space between two columns of the grid.
+const GUTTER: usize = 3;
+
+#[derive(Debug, Args)]
+pub struct GalleryArgs {
+    /// Only show the spinners with this in their name, ignoring case.
+    #[arg(short, long, default_value = "")]
+    filter: String,
+    /// Only show the spinners drawn with this kind of characters.
+    #[arg(long, value_enum)]
+    class: Option<CharClass>,
+    /// The color to start with: a name like `blue` or `bright-red`, a 256-color index or `#rrggbb`.
+    #[arg(short, long, value_parser = parse_color)]
+    color: Option<Color>,
+    /// Print the spinners as a list instead, like when stdout isn't a terminal.
+    #[arg(long)]
+    list: bool,
+}
+
+/// The kind of characters a spinner is drawn with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum CharClass {
+    /// Plain ASCII, which shows up everywhere.
+    Ascii,
+    /// Braille dots.
+    Braille,
+    /// Box drawing, block elements and geometric shapes.
+    Shapes,
+    /// Emoji, which take two columns and need a font that has them.
+    Emoji,
+    /// Anything else.
+    Other,
+}
+
+impl CharClass {
+    fn of(spinner: Spinners) -> Self {
+        let chars = || spinner.frames().iter().flat_map(|frame| frame.chars());
+        if chars().all(|c| c.is_ascii()) {
+            CharClass::Ascii
+        } else if chars().any(|c| c >= '\u{1f000}' || c == '\u{fe0f}') {
+            CharClass::Emoji
+        } else if chars().any(|c| ('\u{2800}'..='\u{28ff}').contains(&c)) {
+            CharClass::Braille
+        } else if chars().any(|c| ('\u{2500}'..='\u{25ff}').contains(&c)) {
+            CharClass::Shapes
+        } else {
+            CharClass::Other
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CharClass::Ascii => "ascii",
+            CharClass::Braille => "braille",
+            CharClass::Shapes => "shapes",
+            CharClass::Emoji => "emoji",
+            CharClass::Other => "other",
+        }
+    }
+}
+
+pub fn run(args: &GalleryArgs) -> ExitCode {
+    let mut gallery = Gallery {
+        filter: args.filter.clone(),
+        class: args.class,
+        color: args.color,
+        selected: 0,
+        scroll: 0,
+        editing: false,
+        started: Instant::now(),
+    };
+    let result = if args.list || !io::stdout().is_terminal() {
+        gallery.list()
+    } else {
+        gallery.browse().and_then(|picked| match picked {
+            Some(spinner) => {
+                let mut stdout = io::stdout();
+                writeln!(stdout, "{}", snippet(spinner, gallery.color))
+            }
+            None => Ok(()),
+        })
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // Nobody to show a closed pipe to.
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("spinoff: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The code that starts a spinner, for `Enter` to print.
+fn snippet(spinner: Spinners, color: Option<Color>) -> String {
+    let imports = match color {
+        Some(_) => "Color, Spinner, Spinners",
+        None => "Spinner, Spinners",
+    };
+    format!(
+        "use spinoff::{{{}}};\n\nlet spinner = {}",
+        imports,
+        constructor(spinner, color)
+    )
+}
+
+/// The line that starts a spinner, shown under the grid.
+fn constructor(spinner: Spinners, color: Option<Color>) -> String {
+    let color = color.map_or_else(|| "None".to_owned(), |color| format!("Color::{:?}", color));
+    format!(
+        "Spinner::new(Spinners::{:?}, \"Loading...\", {});",
+        spinner, color
+    )
+}
+
+struct Gallery {
+    filter: String,
+    class: Option<CharClass>,
+    color: Option<Color>,
+    /// Index of the selected spinner among the ones that are shown.
+    selected: usize,
+    /// The first row of the grid that's on screen.
+    scroll: usize,
+    /// Whether keys go to the filter.
+    editing: bool,
+    started: Instant,
+}
+
+/// Leaves the terminal the way it was found, even on a panic.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = Screen;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Gallery {
+    /// The spinners that pass the filters.
+    fn shown(&self) -> Vec<Spinners> {
+        let filter = self.filter.to_lowercase();
+        Spinners::iter()
+            .filter(|spinner| spinner.to_string().to_lowercase().contains(&filter))
+            .filter(|spinner| {
+                self.class
+                    .is_none_or(|class| CharClass::of(*spinner) == class)
+            })
+            .collect()
+    }
+
+    /// Print the spinners that pass the filters, one per line with all their frames.
+    fn list(&self) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        for spinner in self.shown() {
+            writeln!(
+                stdout,
+                "{:<16} {:>6} {:<8} {}",
+                spinner,
+                format!("{}ms", spinner.interval().as_millis()),
+                CharClass::of(spinner).name(),
+                spinner.frames().join(" ")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Animate the grid until a spinner is picked with `Enter`, or `q` is pressed.
+    fn browse(&mut self) -> io::Result<Option<Spinners>> {
+        let _screen = Screen::enter()?;
+        loop {
+            let shown = self.shown();
+            self.draw(&shown)?;
+            if !event::poll(FRAME_INTERVAL)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            // Some platforms report releases too.
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(None);
+            }
+            if self.editing {
+                self.edit(key);
+                continue;
+            }
+            let columns = self.columns(&shown);
+            let last = shown.len().saturating_sub(1);
+            match key.code {
+                KeyCode::Enter => return Ok(shown.get(self.selected).copied()),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                KeyCode::Left | KeyCode::Char('h') => {
+                    self.selected = self.selected.saturating_sub(1)
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    self.selected = (self.selected + 1).min(last)
+                }
+                KeyCode::Up | KeyCode::Char('k') if self.selected >= columns => {
+                    self.selected -= columns;
+                }
+                KeyCode::Down | KeyCode::Char('j') if self.selected + columns <= last => {
+                    self.selected += columns;
+                }
+                KeyCode::Char('/') => self.editing = true,
+                KeyCode::Tab => self.set_class(1),
+                KeyCode::BackTab => self.set_class(CharClass::value_variants().len()),
+                KeyCode::Char('c') => self.set_color(1),
+                KeyCode::Char('C') => self.set_color(COLORS.len() - 1),
+                _ => {}
+            }
+        }
+    }
+
+    /// Handle a key while typing the filter.
+    fn edit(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => self.editing = false,
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    /// Go `steps` forward through the character classes, with "all" between the last one and the first one.
+    fn set_class(&mut self, steps: usize) {
+        let classes = CharClass::value_variants();
+        let current = self
+            .class
+            .and_then(|class| classes.iter().position(|c| *c == class))
+            .map_or(0, |index| index + 1);
+        let next = (current + steps) % (classes.len() + 1);
+        self.class = next.checked_sub(1).map(|index| classes[index]);
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    /// Go `steps` forward through the colors. A color that isn't in the list starts over from none.
+    fn set_color(&mut self, steps: usize) {
+        let current = COLORS.iter().position(|color| *color == self.color);
+        self.color = match current {
+            Some(index) => COLORS[(index + steps) % COLORS.len()],
+            None => COLORS[0],
+        };
+    }
+
+    /// The widths of the frame and name columns of a cell, and of the whole cell.
+    fn cell_widths(shown: &[Spinners]) -> (usize, usize, usize) {
+        let frame = shown
+            .iter()
+            .flat_map(|spinner| spinner.frames().iter())
+            .map(|frame| frame.width())
+            .max()
+            .unwrap_or(0);
+        let name = shown
+            .iter()
+            .map(|spinner| spinner.to_string().len())
+            .max()
+            .unwrap_or(0);
+        // A marker, the frame, the name and an interval of up to four digits, with a space between each.
+        (frame, name, 2 + frame + 1 + name + 1 + 6)
+    }
+
+    fn columns(&self, shown: &[Spinners]) -> usize {
+        let (width, _) = terminal::size().unwrap_or((80, 24));
+        let (_, _, cell) = Self::cell_widths(shown);
+        ((usize::from(width) + GUTTER) / (cell + GUTTER)).max(1)
+    }
+
+    fn draw(&mut self, shown: &[Spinners]) -> io::Result<()> {
+        let (_, height) = terminal::size().unwrap_or((80, 24));
+        let rows = usize::from(height).saturating_sub(CHROME_HEIGHT).max(1);
+        let columns = self.columns(shown);
+        self.selected = self.selected.min(shown.len().saturating_sub(1));
+        // Keep the selected spinner on screen.
+        let selected_row = self.selected / columns;
+        if selected_row < self.scroll {
+            self.scroll = selected_row;
+        } else if selected_row >= self.scroll + rows {
+            self.scroll = selected_row + 1 - rows;
+        }
+
+        let bold = Style::new().bold();
+        let dimmed = Style::new().dimmed();
+        let mut lines = Vec::new();
+        let cursor = if self.editing { "_" } else { "" };
+        lines.push(format!(
+            "{} of {} spinners   {} {}{}   {} {}   {} {}",
+            bold.apply(&shown.len().to_string()),
+            Spinners::iter().count(),
+            dimmed.apply("filter:"),
+            self.filter,
+            cursor,
+            dimmed.apply("class:"),
+            self.class.map_or("all", CharClass::name),
+            dimmed.apply("color:"),
+            self.color
+                .map_or_else(|| "none".to_owned(), |color| format!("{:?}", color)),
+        ));
+        lines.push(String::new());
+
+        let (frame_width, name_width, _) = Self::cell_widths(shown);
+        let elapsed = self.started.elapsed().as_millis();
+        let frame_style = self
+            .color
+            .map_or_else(Style::new, |color| Style::new().fg(color));
+        let chunks: Vec<&[Spinners]> = shown.chunks(columns).collect();
+        for (row, chunk) in chunks.iter().enumerate().skip(self.scroll).take(rows) {
+            let cells: Vec<String> = chunk
+                .iter()
+                .enumerate()
+                .map(|(column, spinner)| {
+                    let frames = spinner.frames();
+                    let index =
+                        elapsed / spinner.interval().as_millis().max(1) % frames.len() as u128;
+                    let frame = frames[index as usize];
+                    let padding = " ".repeat(frame_width - frame.width());
+                    let name = format!("{:<width$}", spinner.to_string(), width = name_width);
+                    let interval =
+                        format!("{:>6}", format!("{}ms", spinner.interval().as_millis()));
+                    if row * columns + column == self.selected {
+                        format!(
+                            "{} {}{} {} {}",
+                            bold.apply(">"),
+                            frame_style.apply(frame),
+                            padding,
+                            bold.apply(&name),
+                            interval
+                        )
+                    } else {
+                        format!(
+                            "  {}{} {} {}",
+                            frame_style.apply(frame),
+                            padding,
+                            name,
+                            dimmed.apply(&interval)
+                        )
+                    }
+                })
+                .collect();
+            lines.push(cells.join(&" ".repeat(GUTTER)));
+        }
+        if shown.is_empty() {
+            lines.push(dimmed.apply("No spinner matches."));
+        }
+        // Keep the footer in the same place while scrolling.
+        while lines.len() < rows + 2 {
+            lines.push(String::new());
+        }
+
+        lines.push(String::new());
+        lines.push(
+            shown
+                .get(self.selected)
+                .map_or_else(String::new, |spinner| constructor(*spinner, self.color)),
+        );
+        lines.push(String::new());
+        lines.push(dimmed.apply(if self.editing {
+            "type to filter   enter done"
+        } else {
+            "arrows move   / filter   tab class   c color   enter print the code   q quit"
+        }));
+
+        let mut stdout = io::stdout().lock();
+        queue!(stdout, cursor::MoveTo(0, 0))?;
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 {
+                write!(stdout, "\r\n")?;
+            }
+            write!(stdout, "{}", line)?;
+            queue!(stdout, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+        queue!(stdout, terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        stdout.flush()
+    }
+}
@@ -0,0 +1,128 @@
+//! `spinoff`: run a command under a spinner, from a shell script.
+//!
+//! ```text
+//! spinoff --spinner dots --text "Migrating DB" -- ./migrate.sh
+//! ```
+//!
+//! The spinner succeeds or fails with the command, and `spinoff` exits with the command's exit code.
+//! On Unix, `spinoff start` and the commands after it drive one spinner across a whole script, see `daemon`.
+//! `spinoff gallery` previews every spinner, see `gallery`.
+mod args;
+#[cfg(unix)]
+mod daemon;
+mod gallery;
+
+use args::{parse_timeout, SpinnerArgs};
+use clap::{Parser, Subcommand};
+use std::io::{self, Write};
+use std::process::{Command, ExitCode, ExitStatus};
+use std::time::Duration;
+
+/// Run a command under a spinner, and exit with its exit code.
+///
+/// The command's stdout is printed once it exits, so `spinoff` can be used in `$(...)`.
+/// If it fails, the end of its stderr is shown under the failure line, otherwise its stderr is printed after its stdout.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(flatten)]
+    spinner: SpinnerArgs,
+    /// The text next to the spinner. Defaults to the command.
+    #[arg(short, long)]
+    text: Option<String>,
+    /// Show this many of the last lines of output under the spinner while the command runs,
+    /// and all of its output if it fails.
+    #[arg(long, value_name = "LINES", default_value_t = 0)]
+    tail: usize,
+    /// Kill the command, and everything it started, after this many seconds, and exit with code 124.
+    /// The command's stdin is closed, since it can't read from the terminal with a timeout.
+    #[arg(long, value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+    /// The command to run, after `--`.
+    #[arg(last = true, required = true)]
+    command: Vec<String>,
+    #[command(subcommand)]
+    action: Option<Action>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Action {
+    /// Drive one spinner from separate commands.
+    #[cfg(unix)]
+    #[command(flatten)]
+    Daemon(daemon::Action),
+    /// Preview every spinner at once, and print the code for the one you pick.
+    Gallery(gallery::GalleryArgs),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // Don't leave a hidden cursor and a half-drawn frame behind on Ctrl-C.
+    // The command gets the signal too, and we go down the way we would have without a handler.
+    // The background spinner of `spinoff start` handles them itself, to remove its socket.
+    #[cfg(unix)]
+    let serving = matches!(
+        cli.action,
+        Some(Action::Daemon(daemon::Action::Serve { .. }))
+    );
+    #[cfg(unix)]
+    if !serving {
+        if let Err(err) = spinoff::install_signal_handlers(spinoff::Cleanup::default()) {
+            eprintln!("spinoff: failed to install the signal handlers: {}", err);
+        }
+    }
+    match cli.action {
+        #[cfg(unix)]
+        Some(Action::Daemon(action)) => daemon::run(action),
+        Some(Action::Gallery(args)) => gallery::run(&args),
+        None => run(cli),
+    }
+}
+
+fn run(cli: Cli) -> ExitCode {
+    let text = cli.text.unwrap_or_else(|| cli.command.join(" "));
+    let mut spinner = cli.spinner.start(&text);
+    spinner.set_output_tail(cli.tail);
+    spinner.set_command_timeout(cli.timeout);
+
+    let mut command = Command::new(&cli.command[0]);
+    command.args(&cli.command[1..]);
+    match spinner.run_command(&mut command) {
+        Ok(output) => {
+            // Whatever's reading our stdout may have gone away, which isn't the command's failure.
+            let _ = io::stdout().write_all(&output.stdout);
+            // A failure already showed it under the finish line, but warnings from a success shouldn't be lost.
+            if output.status.success() {
+                let _ = io::stderr().write_all(&output.stderr);
+            }
+            ExitCode::from(exit_code(output.status))
+        }
+        // The codes `timeout` and shells use for commands that ran too long, can't be found, or can't be run.
+        Err(err) => ExitCode::from(match err.kind() {
+            io::ErrorKind::TimedOut => 124,
+            io::ErrorKind::NotFound => 127,
+            _ => 126,
+        }),
+    }
+}
+
+/// The exit code to pass on for how the command exited, like a shell would.
+fn exit_code(status: ExitStatus) -> u8 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128u8.saturating_add(u8::try_from(signal).unwrap_or(u8::MAX));
+        }
+    }
+    // Exit codes are truncated to a byte on Unix anyway, and anything else still has to be a failure.
+    status
+        .code()
+        .and_then(|code| u8::try_from(code).ok())
+        .unwrap_or(1)
+}
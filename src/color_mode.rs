@@ -0,0 +1,81 @@
+use crate::Streams;
+use once_cell::sync::Lazy;
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Whether spinners and finish lines are colored.
+/// By default, `spinoff` uses `ColorMode::Auto`.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ColorMode {
+    /// Always emit colors and styles.
+    Always,
+    /// Follow `NO_COLOR`, `CLICOLOR`, `CLICOLOR_FORCE` and `FORCE_COLOR`,
+    /// and only color output that goes to a terminal.
+    #[default]
+    Auto,
+    /// Never emit colors and styles.
+    Never,
+}
+
+/// The mode set through `set_color_mode`, shared by every spinner.
+static COLOR_MODE: AtomicU8 = AtomicU8::new(ColorMode::Auto as u8);
+
+/// What the environment variables ask for, `None` if they leave it up to the terminal.
+/// Only looked up once.
+static ENV_PREFERENCE: Lazy<Option<bool>> =
+    Lazy::new(|| env_preference(|name| env::var(name).ok()));
+
+/// Internal function deciding what a set of environment variables asks for, looked up through `var`.
+///
+/// `FORCE_COLOR` and `CLICOLOR_FORCE` win over `NO_COLOR`, which wins over `CLICOLOR`.
+/// Empty variables count as unset.
+pub fn env_preference(var: impl Fn(&str) -> Option<String>) -> Option<bool> {
+    let var = |name: &str| var(name).filter(|value| !value.is_empty());
+    if let Some(value) = var("FORCE_COLOR") {
+        return Some(value != "0" && value != "false");
+    }
+    if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+        Some(true)
+    } else if var("NO_COLOR").is_some() || var("CLICOLOR").is_some_and(|value| value == "0") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Set whether spinners and finish lines are colored, for every spinner in the process.
+///
+/// This is meant to be wired to a `--color` flag. Explicit `Always` and `Never` take precedence over the environment.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// set_color_mode(ColorMode::Never);
+/// let sp = Spinner::new(Spinners::Dots, "Plain text only...", Color::Blue);
+/// sp.success("Done!");
+/// # set_color_mode(ColorMode::Auto);
+/// ```
+pub fn set_color_mode(mode: ColorMode) {
+    COLOR_MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+/// Returns the mode set through `set_color_mode`.
+#[must_use]
+pub fn color_mode() -> ColorMode {
+    match COLOR_MODE.load(Ordering::Relaxed) {
+        mode if mode == ColorMode::Always as u8 => ColorMode::Always,
+        mode if mode == ColorMode::Never as u8 => ColorMode::Never,
+        _ => ColorMode::Auto,
+    }
+}
+
+/// Internal function deciding whether output to the given stream should be colored.
+pub fn should_colorize(stream: Streams) -> bool {
+    match color_mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => ENV_PREFERENCE.unwrap_or_else(|| stream.is_terminal()),
+    }
+}
@@ -0,0 +1,238 @@
+use crate::elapsed::format_duration;
+#[cfg(unix)]
+use crate::hooks::CommandGroup;
+use crate::printer::{strip_ansi, truncate};
+use crate::shared::{lock, Shared};
+use crate::theme::Finish;
+use crate::{Spinner, Style};
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How many lines from the end of stderr are shown when a command fails.
+const STDERR_TAIL: usize = 10;
+
+/// How often a command with a timeout is checked on.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+impl Spinner {
+    /// Run a command with its output captured, spinning until it exits.
+    ///
+    /// The spinner finishes with `success` if the command exits with code 0, and with `fail` otherwise,
+    /// along with the exit status and the last lines of stderr. Either way, the captured output is returned.
+    ///
+    /// stdin is left the way the command has it set up, unless there's a timeout, see [`Spinner::set_command_timeout`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command couldn't be started or waited for, after failing the spinner with it.
+    /// If it ran longer than [`Spinner::set_command_timeout`] allows, the error is of kind `TimedOut`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::process::Command;
+    /// #
+    /// # fn main() -> std::io::Result<()> {
+    /// let sp = Spinner::new(Spinners::Dots, "Checking rustc...", None);
+    /// let output = sp.run_command(Command::new("rustc").arg("--version"))?;
+    /// assert!(output.status.success());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn run_command(self, command: &mut Command) -> io::Result<Output> {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        // Killing the whole group also gets what the command started, which would otherwise keep the pipes open.
+        // Outside of the terminal's foreground group, reading from the terminal would stop the command until it's killed.
+        #[cfg(unix)]
+        if self.command_timeout.is_some() {
+            std::os::unix::process::CommandExt::process_group(command, 0);
+            command.stdin(Stdio::null());
+        }
+        let (output, log) = match self.capture(command) {
+            Ok(Some(output)) => output,
+            Ok(None) => {
+                let timeout = self.command_timeout.unwrap_or_default();
+                let msg = format!(
+                    "{} (timed out after {})",
+                    self.msg,
+                    format_duration(timeout)
+                );
+                self.fail(msg);
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "the command ran longer than its timeout",
+                ));
+            }
+            Err(err) => {
+                let msg = format!("{} (failed to run: {})", self.msg, err);
+                self.fail(msg);
+                return Err(err);
+            }
+        };
+        if output.status.success() {
+            let msg = self.msg.clone();
+            self.success(msg);
+        } else {
+            let msg = format!("{} ({})", self.msg, output.status);
+            // With a live tail, the whole log was kept to be shown here.
+            let details = if self.output_tail == 0 {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let lines: Vec<&str> = stderr.lines().collect();
+                indent(&lines[lines.len().saturating_sub(STDERR_TAIL)..])
+            } else {
+                indent(&log)
+            };
+            let _ = self.finish_with_details(Finish::Fail, msg, &details);
+        }
+        Ok(output)
+    }
+
+    /// Run a command, drawing its last lines of output under the spinner if there's a tail.
+    /// Returns the output and every line of stdout and stderr in the order they came in,
+    /// or `None` if it was killed for running too long.
+    fn capture(&self, command: &mut Command) -> io::Result<Option<(Output, Vec<String>)>> {
+        let mut child = command.spawn()?;
+        #[cfg(unix)]
+        let _group = self
+            .command_timeout
+            .map(|_| CommandGroup::register(child.id()));
+        let stdout = child.stdout.take().expect("error: stdout is piped");
+        let stderr = child.stderr.take().expect("error: stderr is piped");
+        let log = Arc::new(Mutex::new(Vec::new()));
+        // Not scoped, so a reader stuck on a pipe that something else still holds can be left behind.
+        let read = |source: Box<dyn Read + Send>| {
+            let shared = Arc::clone(&self.shared);
+            let log = Arc::clone(&log);
+            let lines = self.output_tail;
+            thread::spawn(move || pump(source, &shared, &log, lines))
+        };
+        let stdout = read(Box::new(stdout));
+        let stderr = read(Box::new(stderr));
+        let status = match self.command_timeout {
+            Some(timeout) => wait_timeout(&mut child, timeout),
+            None => child.wait().map(Some),
+        };
+        let output = match status {
+            Ok(Some(status)) => {
+                let stdout = stdout.join().expect("error: the stdout reader panicked");
+                let stderr = stderr.join().expect("error: the stderr reader panicked");
+                Some(Output {
+                    status,
+                    stdout: stdout?,
+                    stderr: stderr?,
+                })
+            }
+            Ok(None) => None,
+            Err(err) => {
+                lock(&self.shared.output_tail).clear();
+                return Err(err);
+            }
+        };
+        // The command is done, collapse the tail.
+        lock(&self.shared.output_tail).clear();
+        let log = lock(&log).drain(..).collect();
+        Ok(output.map(|output| (output, log)))
+    }
+}
+
+/// Internal function for waiting on a command for up to `timeout`, and killing it after that.
+/// Returns `None` if it was killed.
+fn wait_timeout(
+    child: &mut Child,
+    timeout: Duration,
+) -> io::Result<Option<std::process::ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            kill(child)?;
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Internal function for killing a command along with its process group, which it leads.
+fn kill(child: &mut Child) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let group = libc::pid_t::try_from(child.id())
+            .map_err(|_| io::Error::other("the process id doesn't fit in a pid_t"))?;
+        // SAFETY: `kill` has no memory safety requirements.
+        if unsafe { libc::kill(-group, libc::SIGKILL) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    #[cfg(not(unix))]
+    child.kill()?;
+    child.wait().map(|_| ())
+}
+
+/// Internal function for reading a stream of a command to the end, keeping the last `lines` lines in the spinner's tail.
+fn pump<R: Read>(
+    source: R,
+    shared: &Shared,
+    log: &Mutex<Vec<String>>,
+    lines: usize,
+) -> io::Result<Vec<u8>> {
+    let mut captured = Vec::new();
+    if lines == 0 {
+        let mut source = source;
+        source.read_to_end(&mut captured)?;
+        return Ok(captured);
+    }
+    let mut reader = BufReader::new(source);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(captured);
+        }
+        captured.extend_from_slice(&line);
+        let text = String::from_utf8_lossy(&line);
+        // Progress bars redraw themselves with carriage returns, only what's left at the end is worth showing.
+        let text = text.trim_end_matches(['\n', '\r']);
+        let text = strip_ansi(text.rsplit('\r').next().unwrap_or_default());
+        lock(log).push(text.clone());
+        let mut tail = lock(&shared.output_tail);
+        tail.push_back(text);
+        while tail.len() > lines {
+            tail.pop_front();
+        }
+    }
+}
+
+/// Internal function for adding the tail of a command's output to the lines drawn under a spinner.
+pub fn render_tail(shared: &Shared, colors: bool, lines: &mut Vec<String>) {
+    let tail = lock(&shared.output_tail);
+    if tail.is_empty() {
+        return;
+    }
+    // Lines that wrap would throw off how many lines are cleared on the next frame.
+    let width = shared.stream.width().unwrap_or(80).saturating_sub(2);
+    let dimmed = Style::new().dimmed();
+    for line in tail.iter() {
+        let line = truncate(line, width);
+        lines.push(if colors {
+            format!("  {}", dimmed.apply(line))
+        } else {
+            format!("  {}", line)
+        });
+    }
+}
+
+/// Internal function for output lines, dimmed and indented to go under a finish line.
+fn indent<T: AsRef<str>>(lines: &[T]) -> Vec<String> {
+    let dimmed = Style::new().dimmed();
+    lines
+        .iter()
+        .map(|line| format!("  {}", dimmed.apply(line.as_ref())))
+        .collect()
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+/// Controls where the elapsed time of a spinner is displayed.
+/// By default, `spinoff` uses `Elapsed::Hidden`.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Elapsed {
+    /// Never display the elapsed time.
+    #[default]
+    Hidden,
+    /// Render a live timer next to the message while spinning.
+    Live,
+    /// Append the final duration to the line printed when the spinner finishes.
+    OnFinish,
+    /// Render a live timer and append the final duration when finished.
+    Always,
+}
+
+impl Elapsed {
+    // Whether the render loop should draw the timer on every frame.
+    pub fn is_live(self) -> bool {
+        matches!(self, Elapsed::Live | Elapsed::Always)
+    }
+    // Whether the finish line should carry the final duration.
+    pub fn on_finish(self) -> bool {
+        matches!(self, Elapsed::OnFinish | Elapsed::Always)
+    }
+}
+
+/// Internal function for formatting a duration in a short, human readable way.
+/// Durations under a minute are shown with one decimal (`12.4s`), longer ones as `1m 05s` or `1h 02m 05s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!(
+            "{}h {:02}m {:02}s",
+            secs / 3600,
+            (secs % 3600) / 60,
+            secs % 60
+        )
+    }
+}
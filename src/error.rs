@@ -0,0 +1,36 @@
+use std::fmt;
+use std::io;
+
+/// Errors returned by the `try_*` methods of a spinner.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Writing to the stream, or starting the spinner thread, failed.
+    Io(io::Error),
+    /// The thread printing the spinner panicked.
+    ThreadPanicked,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "spinner I/O failed: {}", err),
+            Error::ThreadPanicked => write!(f, "the spinner thread panicked"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::ThreadPanicked => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
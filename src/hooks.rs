@@ -0,0 +1,229 @@
+use crate::shared::{lock, Shared};
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, Once, Weak};
+use std::thread;
+
+/// What happens to the spinners that are still running when the process is interrupted or panics.
+#[derive(Clone, Debug)]
+pub enum Cleanup {
+    /// Clear the spinner line.
+    Clear,
+    /// Replace the spinner with a failure line, like `✗ Interrupted`.
+    Fail(Cow<'static, str>),
+}
+
+impl Default for Cleanup {
+    fn default() -> Self {
+        Cleanup::Fail("Interrupted".into())
+    }
+}
+
+thread_local! {
+    /// Set while a task of a `TaskList` runs on this thread. Its panics are the task's failure, not the process's.
+    static IN_TASK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// What `install_panic_hook` was asked to do, for the spinners dropped while unwinding from a panic.
+static PANIC_CLEANUP: Mutex<Option<Cleanup>> = Mutex::new(None);
+
+/// Every spinner created so far that is still alive.
+static ACTIVE: Lazy<Mutex<Vec<Weak<Shared>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// The process groups of the commands running under a timeout, which don't get the terminal's signals themselves.
+#[cfg(unix)]
+static COMMAND_GROUPS: Lazy<Mutex<Vec<u32>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Makes the process group of a running command known to the signal handlers, until it's dropped.
+#[cfg(unix)]
+pub struct CommandGroup(u32);
+
+#[cfg(unix)]
+impl CommandGroup {
+    pub fn register(group: u32) -> Self {
+        lock(&COMMAND_GROUPS).push(group);
+        CommandGroup(group)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for CommandGroup {
+    fn drop(&mut self) {
+        lock(&COMMAND_GROUPS).retain(|group| *group != self.0);
+    }
+}
+
+/// Internal function for making a spinner known to the interrupt hooks.
+pub fn register(shared: &Arc<Shared>) {
+    let mut active = lock(&ACTIVE);
+    active.retain(|shared| shared.strong_count() > 0);
+    active.push(Arc::downgrade(shared));
+}
+
+/// Internal function for finalizing every running spinner.
+fn cleanup_all(cleanup: &Cleanup) {
+    let active: Vec<_> = lock(&ACTIVE).iter().filter_map(Weak::upgrade).collect();
+    for shared in active {
+        // We're on the way out, there's nobody to report a failed write to.
+        let _ = shared.interrupt(cleanup);
+    }
+}
+
+/// Internal function for clearing every running spinner while the panic message is printed.
+/// Returns the spinners to resume afterwards.
+fn hold_all() -> Vec<Arc<Shared>> {
+    let active: Vec<_> = lock(&ACTIVE).iter().filter_map(Weak::upgrade).collect();
+    active
+        .into_iter()
+        .filter(|shared| shared.hold().unwrap_or(false))
+        .collect()
+}
+
+/// Internal function returning how to finalize a spinner that is dropped without being finished.
+/// Only set while the thread unwinds from a panic outside of a task, with `install_panic_hook` called.
+pub fn unwinding_cleanup() -> Option<Cleanup> {
+    if !thread::panicking() || IN_TASK.with(Cell::get) {
+        return None;
+    }
+    lock(&PANIC_CLEANUP).clone()
+}
+
+/// Internal function for running a task of a `TaskList`, catching its panic without any hook or output.
+///
+/// The message would land in the middle of the list, and finalizing the spinners would end the list early.
+pub fn catch_task_panic<R>(task: impl FnOnce() -> R) -> thread::Result<R> {
+    static QUIET_HOOK: Once = Once::new();
+    QUIET_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IN_TASK.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+    IN_TASK.with(|flag| flag.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(task));
+    IN_TASK.with(|flag| flag.set(false));
+    result
+}
+
+/// Clean up running spinners when a panic happens, and finalize the ones the panic takes down.
+///
+/// Running spinners are cleared and the cursor is shown before the panic message is printed,
+/// by the previously installed panic hook so the default panic output is kept.
+/// They're drawn again afterwards, since the panic may still be caught.
+/// A spinner that is dropped while its thread unwinds from the panic is finalized with `cleanup`.
+///
+/// # Notes
+///
+/// * With `panic = "abort"`, every running spinner is finalized before the panic message instead.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// install_panic_hook(Cleanup::Fail("Crashed".into()));
+/// let sp = Spinner::new(Spinners::Dots, "Working...", None);
+/// // A panic from here on prints `✗ Crashed` instead of leaving a half-drawn frame behind.
+/// sp.success("Done!");
+/// ```
+pub fn install_panic_hook(cleanup: Cleanup) {
+    *lock(&PANIC_CLEANUP) = Some(cleanup.clone());
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // A panicking task fails on its own, the list and the other spinners keep going.
+        if IN_TASK.with(Cell::get) {
+            return;
+        }
+        if cfg!(panic = "abort") {
+            cleanup_all(&cleanup);
+            previous(info);
+            return;
+        }
+        let held = hold_all();
+        previous(info);
+        for shared in held {
+            shared.resume();
+        }
+    }));
+}
+
+/// Finalize running spinners on `SIGINT`, `SIGTERM` and `SIGHUP`, then let the signal do what it would have done.
+///
+/// The signal is passed on to commands running under [`Spinner::set_command_timeout`](crate::Spinner::set_command_timeout) first.
+///
+/// Only available on Unix, with the `signals` feature.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// # fn main() -> Result<(), spinoff::Error> {
+/// install_signal_handlers(Cleanup::default())?;
+/// let sp = Spinner::new(Spinners::Dots, "Press Ctrl-C...", None);
+/// sp.clear();
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(all(unix, feature = "signals"))]
+pub fn install_signal_handlers(cleanup: Cleanup) -> Result<(), crate::Error> {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP])?;
+    std::thread::Builder::new()
+        .name("spinoff-signals".into())
+        .spawn(move || {
+            for signal in signals.forever() {
+                // Commands in a group of their own would keep running after we're gone.
+                for group in lock(&COMMAND_GROUPS).iter() {
+                    if let Ok(group) = libc::pid_t::try_from(*group) {
+                        // SAFETY: `kill` has no memory safety requirements.
+                        unsafe { libc::kill(-group, signal) };
+                    }
+                }
+                cleanup_all(&cleanup);
+                // Terminates the process the same way the signal would have without us.
+                let _ = signal_hook::low_level::emulate_default_handler(signal);
+            }
+        })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Spinner, Spinners};
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn caught_panic_leaves_the_spinner_running() {
+        install_panic_hook(Cleanup::default());
+        let sp = Spinner::new(Spinners::Dots, "Working...", None);
+        let result = panic::catch_unwind(|| "eighty".parse::<u16>().expect("a number"));
+        assert!(result.is_err());
+        assert!(sp.shared.still_spinning.load(Ordering::Relaxed));
+        assert!(!sp.shared.interrupted.load(Ordering::Relaxed));
+        assert!(!sp.is_paused());
+        sp.success("finished fine");
+    }
+
+    #[test]
+    fn spinner_dropped_by_a_panic_is_finalized() {
+        install_panic_hook(Cleanup::default());
+        let mut shared = None;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let sp = Spinner::new(Spinners::Dots, "Working...", None);
+            shared = Some(Arc::clone(&sp.shared));
+            "eighty".parse::<u16>().expect("a number")
+        }));
+        assert!(result.is_err());
+        let shared = shared.expect("the spinner was created");
+        assert!(shared.interrupted.load(Ordering::Relaxed));
+        assert!(!shared.still_spinning.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Spinner, Spinners};
+use std::fmt::Display;
+use std::iter::FusedIterator;
+
+/// Spin while consuming an iterator.
+///
+/// Implemented for every iterator.
+pub trait SpinnerIterator: Iterator + Sized {
+    /// Show a spinner with the number of items taken so far, out of the total when it's known.
+    ///
+    /// The spinner finishes with `success` once the iterator runs out, and is cleared if it's dropped before that,
+    /// like when a loop over it breaks early.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let files = vec!["a.txt", "b.txt", "c.txt"];
+    /// for _file in files.iter().spinner(Spinners::Dots, "Processing") {
+    ///     sleep(Duration::from_millis(300));
+    /// }
+    /// ```
+    fn spinner<T: Display>(self, spinner_type: Spinners, msg: T) -> SpinnerIter<Self> {
+        self.with_spinner(Spinner::new(spinner_type, msg, None))
+    }
+
+    /// Like [`SpinnerIterator::spinner`], with a spinner that's already set up.
+    ///
+    /// Set a length on the spinner with [`Spinner::set_length`] to get a progress bar instead of the frames.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let sp = Spinner::new_with_stream(Spinners::Line, "Processing", Color::Cyan, Streams::Stderr);
+    /// let total: u32 = (1..=100).with_spinner(sp).sum();
+    /// assert_eq!(total, 5050);
+    /// ```
+    fn with_spinner(self, mut spinner: Spinner) -> SpinnerIter<Self> {
+        let length = match self.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower as u64),
+            _ => None,
+        };
+        spinner.count_items(length);
+        SpinnerIter {
+            iter: self,
+            spinner: Some(spinner),
+        }
+    }
+}
+
+impl<I: Iterator> SpinnerIterator for I {}
+
+/// An iterator with a spinner, returned by [`SpinnerIterator::spinner`].
+#[derive(Debug)]
+pub struct SpinnerIter<I> {
+    iter: I,
+    /// Taken once the iterator runs out and the spinner is finished.
+    spinner: Option<Spinner>,
+}
+
+impl<I: Iterator> Iterator for SpinnerIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(item) = self.iter.next() else {
+            if let Some(spinner) = self.spinner.take() {
+                let msg = spinner.msg.clone();
+                spinner.success(msg);
+            }
+            return None;
+        };
+        if let Some(spinner) = &self.spinner {
+            spinner.inc(1);
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for SpinnerIter<I> {}
+
+impl<I: FusedIterator> FusedIterator for SpinnerIter<I> {}
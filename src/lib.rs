@@ -21,36 +21,141 @@
 //! ### Colors
 //!
 //! You can also color your spinners without any hassle. Simply pass a color to the `color` option.
-//! There are 9 colors available: blue, green, red, yellow, cyan, white, magenta, black and a custom variant.
+//! There are 8 basic colors available (blue, green, red, yellow, cyan, white, magenta and black), their bright variants,
+//! the ANSI 256-color palette and a custom true color variant.
+//! Colors are downgraded automatically on terminals that advertise fewer colors.
+//!
+//! Colors are left out when the output isn't a terminal, and `NO_COLOR`, `CLICOLOR`, `CLICOLOR_FORCE` and `FORCE_COLOR` are honored.
+//! Use [`set_color_mode`] to override this for every spinner, for example from a `--color` flag.
 //! Don't want any of that? Simply pass `None` to the `color` option.
+//!
+//! The message can be styled on its own with [`Spinner::set_text_style`].
+//! Already-styled text, like a `colored::ColoredString`, can also be passed anywhere a message is expected.
+//!
+//! ### Themes
+//!
+//! The symbols printed by [`Spinner::success`], [`Spinner::fail`], [`Spinner::warn`] and [`Spinner::info`] come from a [`Theme`].
+//! Pick one of the built-in themes or make your own, and set it per spinner or with [`set_default_theme`].
+//!
+//! ### Task lists
+//!
+//! A [`TaskList`] runs a list of named tasks, one after the other or a few at a time, and shows them as a checklist:
+//! pending, spinning, then marked with the theme's symbols as they finish.
+//!
+//! ### Sub-tasks
+//!
+//! [`Spinner::child`] adds a [`Child`] spinner drawn indented under its parent. Children can have children of their own,
+//! collapse into a single line when they finish, and fail their parents when they fail.
+//!
+//! ### Progress
+//!
+//! When the amount of work is known, [`Spinner::set_length`] turns the spinner into a progress bar,
+//! moved along with [`Spinner::set_position`] and [`Spinner::inc`].
+//!
+//! Use [`Spinner::set_unit`] to show the position as a count with its rate instead, or next to the bar,
+//! and [`Spinner::counter`] to count from other threads.
+//!
+//! [`Spinner::wrap_read`] and [`Spinner::wrap_write`] count the bytes going through a reader or writer,
+//! which are also `AsyncRead` and `AsyncWrite` with the `tokio` feature.
+//!
+//! To spin while going through an iterator, use [`SpinnerIterator::spinner`]: `for file in files.iter().spinner(Spinners::Dots, "Processing")`.
+//! With the `rayon` feature, `ParallelSpinnerIterator` does the same for parallel iterators.
+//!
+//! ### Commands
+//!
+//! [`Spinner::run_command`] runs a `std::process::Command` under the spinner, and succeeds or fails with its exit status.
+//! Use [`Spinner::set_output_tail`] to see the last lines of its output while it runs.
+//!
+//! With the `cli` feature, the crate also builds a `spinoff` binary that does the same from shell scripts:
+//! `spinoff --text "Migrating DB" -- ./migrate.sh`. On Unix, `spinoff start`, `update`, `step` and `succeed`
+//! drive one spinner across a whole script, and `spinoff gallery` previews every spinner at once.
+//!
+//! ### Elapsed time
+//!
+//! Every method that finishes a spinner returns how long it was running.
+//! Use [`Spinner::set_elapsed`] to also show a live timer next to the message or append the final duration to the finish line.
+//!
+//! ### Interrupts and panics
+//!
+//! Call [`install_panic_hook`], and with the `signals` feature `install_signal_handlers`, to clear or finalize
+//! running spinners (e.g. `✗ Interrupted`) when a panic takes them down or the process gets `SIGINT`, `SIGTERM` or `SIGHUP`.
+//!
+//! ### Errors
+//!
+//! Spinners are cosmetic, so the regular methods never panic on I/O errors: a spinner printing to a closed pipe simply stops drawing.
+//! If you want to know about failures, use the `try_*` variants like [`Spinner::try_new`] and [`Spinner::try_success`], which return an [`Error`].
 #![allow(clippy::nursery)]
-use colored::{Colorize};
-use std::borrow::Cow;
-use std::io::Write;
-use std::sync::{atomic::AtomicBool, Arc};
+use std::fmt::Display;
+use std::io;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+mod color_mode;
+mod command;
+mod elapsed;
+mod error;
+mod hooks;
+mod iter;
+#[cfg(feature = "rayon")]
+mod par_iter;
 mod printer;
+mod progress;
+mod shared;
 mod spinner_data;
 mod spinner_enum;
 mod streams;
+mod style;
+mod task_list;
+mod theme;
+mod tree;
+mod wrap;
 
+use color_mode::should_colorize;
+pub use color_mode::{color_mode, set_color_mode, ColorMode};
+use elapsed::format_duration;
+pub use elapsed::Elapsed;
+pub use error::Error;
+#[cfg(all(unix, feature = "signals"))]
+pub use hooks::install_signal_handlers;
+pub use hooks::{install_panic_hook, Cleanup};
+pub use iter::{SpinnerIter, SpinnerIterator};
+#[cfg(feature = "rayon")]
+pub use par_iter::{ParSpinnerIter, ParallelSpinnerIterator};
 pub use printer::Color;
-use printer::{colorize, delete_last_line};
+use printer::{hide_cursor, paint, show_cursor, strip_ansi, visible_width};
+use progress::Rate;
+pub use progress::{Counter, Unit};
+use shared::{lock, Shared};
 use spinner_data::SPINNER_FRAMES;
 pub use spinner_enum::Spinners;
-pub use streams::Streams;
+pub use streams::{Routing, Streams};
+pub use style::Style;
+pub use task_list::{Outcome, Summary, TaskList, TaskReport};
+use theme::Finish;
+pub use theme::{default_theme, set_default_theme, Theme};
+pub use tree::Child;
+pub use wrap::{CountingReader, CountingWriter};
 
 /// Terminal spinner.
 #[derive(Debug)]
 pub struct Spinner {
-    thread_handle: Option<JoinHandle<()>>,
-    /// This struct has an `Arc<AtomicBool>` field, which is later used in the `stop` type methods to stop the thread printing the spinner.
-    still_spinning: Arc<AtomicBool>,
+    thread_handle: Option<JoinHandle<io::Result<()>>>,
+    /// This struct has an `Arc<Shared>` field, holding the atomic bool which is later used in the `stop` type methods to stop the thread printing the spinner.
+    shared: Arc<Shared>,
     spinner_type: Spinners,
-    msg: Cow<'static, str>,
-    stream: Streams,
+    msg: String,
     color: Option<Color>,
+    background: Option<Color>,
+    text_style: Style,
+    hide_cursor: bool,
+    output_tail: usize,
+    command_timeout: Option<Duration>,
+    unit: Option<Unit>,
+    timer: Elapsed,
+    started: Instant,
+    step_started: Instant,
 }
 
 impl Spinner {
@@ -80,7 +185,7 @@ impl Spinner {
     /// * This function outputs to the `stdout` stream. If you want to use a different stream, use the [`Spinner::new_with_stream`] function.
     pub fn new<T, U>(spinner_type: Spinners, msg: T, color: U) -> Self
     where
-        T: Into<Cow<'static, str>>,
+        T: Display,
         U: Into<Option<Color>>,
     {
         Self::new_with_stream(spinner_type, msg, color, Streams::default())
@@ -112,59 +217,548 @@ impl Spinner {
     ///
     pub fn new_with_stream<T, U>(spinner_type: Spinners, msg: T, color: U, stream: Streams) -> Self
     where
-        T: Into<Cow<'static, str>>,
+        T: Display,
         U: Into<Option<Color>>,
     {
-        let still_spinning = Arc::new(AtomicBool::new(true));
-        // Gain ownership of the message and color for the thread to use
-        let msg = msg.into();
-        let color = color.into();
-        // We use atomic bools to make the thread stop itself when the `spinner.stop()` method is called.
-        let handle = thread::spawn({
-            // Clone the atomic bool so that we can use it in the thread and return the original one later.
-            let still_spinning = Arc::clone(&still_spinning);
-            let msg = msg.clone();
-            move || {
-                let spinner_data = SPINNER_FRAMES
-                    .get(&spinner_type)
-                    .expect("error: invalid spinner type");
-                // Iterate over all the frames of the spinner while the atomic bool is true.
-                let frames = spinner_data
-                    .frames
-                    .iter()
-                    .cycle()
-                    .take_while(|_| still_spinning.load(std::sync::atomic::Ordering::Relaxed));
-                // Dynamically delete the last line of the terminal depending on the length of the message + spinner.
-                let mut last_length = 0;
-                for frame in frames {
-                    let frame_str = format!("{} {}", colorize(color, frame), msg);
-                    // Get us back to the start of the line.
-                    delete_last_line(last_length, stream);
-                    last_length = frame_str.bytes().len();
-                    write!(stream, "{}", frame_str);
-                    stream
-                        .get_stream()
-                        .flush()
-                        .expect("error: failed to flush stream");
-
-                    thread::sleep(std::time::Duration::from_millis(
-                        u64::from(spinner_data.interval)
-                    ));
-                }
-                delete_last_line(last_length, stream);
-            }
-        });
+        Self::try_new_with_stream(spinner_type, msg, color, stream)
+            .expect("error: failed to start the spinner thread")
+    }
 
-        // Return a Spinner struct
-        Self {
-            thread_handle: Some(handle),
-            still_spinning,
+    /// Create a new spinner, returning an error if the spinner thread can't be started.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Loading...", None)?;
+    /// sp.try_success("Loaded")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_new<T, U>(spinner_type: Spinners, msg: T, color: U) -> Result<Self, Error>
+    where
+        T: Display,
+        U: Into<Option<Color>>,
+    {
+        Self::try_new_with_stream(spinner_type, msg, color, Streams::default())
+    }
+
+    /// Create a new spinner outputting to a specific stream, returning an error if the spinner thread can't be started.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new_with_stream(Spinners::Dots, "Loading...", None, Streams::Stderr)?;
+    /// sp.try_clear()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_new_with_stream<T, U>(
+        spinner_type: Spinners,
+        msg: T,
+        color: U,
+        stream: Streams,
+    ) -> Result<Self, Error>
+    where
+        T: Display,
+        U: Into<Option<Color>>,
+    {
+        let mut spinner = Self {
+            thread_handle: None,
+            shared: Arc::new(Shared::new(stream)),
             spinner_type,
-            msg,
-            stream,
-            color,
+            msg: msg.to_string(),
+            color: color.into(),
+            background: None,
+            text_style: Style::default(),
+            hide_cursor: true,
+            output_tail: 0,
+            command_timeout: None,
+            unit: None,
+            timer: Elapsed::default(),
+            started: Instant::now(),
+            step_started: Instant::now(),
+        };
+        hooks::register(&spinner.shared);
+        spinner.start_spinner_thread()?;
+        Ok(spinner)
+    }
+
+    /// Choose where the elapsed time is displayed.
+    ///
+    /// The timer keeps counting from the moment the spinner was created, even across calls to `update`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Building...", None);
+    /// sp.set_elapsed(Elapsed::Always);
+    /// sleep(Duration::from_millis(800));
+    /// let took = sp.success("Built");
+    /// assert!(took >= Duration::from_millis(800));
+    /// ```
+    ///
+    pub fn set_elapsed(&mut self, timer: Elapsed) {
+        self.restart(|spinner| spinner.timer = timer);
+    }
+
+    /// Set the background color of the spinner frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Loading...", Color::BrightWhite);
+    /// sp.set_background(Color::Ansi256(24));
+    /// sleep(Duration::from_millis(800));
+    /// sp.stop();
+    /// ```
+    ///
+    pub fn set_background<U>(&mut self, background: U)
+    where
+        U: Into<Option<Color>>,
+    {
+        self.restart(|spinner| spinner.background = background.into());
+    }
+
+    /// Style the message text, independently of the spinner frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Compiling...", Color::Green);
+    /// sp.set_text_style(Style::new().fg(Color::White).bold());
+    /// sleep(Duration::from_millis(800));
+    /// sp.success(colored::Colorize::green("Compiled"));
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// * Messages that are already styled, such as a `colored::ColoredString`, are accepted everywhere a message is.
+    ///
+    pub fn set_text_style(&mut self, style: Style) {
+        self.restart(|spinner| spinner.text_style = style);
+    }
+
+    /// Choose whether the terminal cursor is hidden while the spinner is spinning.
+    ///
+    /// The cursor is hidden by default when the spinner is printed on a terminal,
+    /// and shown again whenever the spinner stops, including when it's dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Keeping the cursor around...", None);
+    /// sp.set_hide_cursor(false);
+    /// sleep(Duration::from_millis(800));
+    /// sp.stop();
+    /// ```
+    ///
+    pub fn set_hide_cursor(&mut self, hide: bool) {
+        self.restart(|spinner| spinner.hide_cursor = hide);
+    }
+
+    /// Show the last `lines` lines of output of [`Spinner::run_command`] under the spinner, dimmed, while the command runs.
+    ///
+    /// The lines go away once the command exits. If it failed, its whole output is printed under the failure line,
+    /// instead of the end of stderr.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::process::Command;
+    /// #
+    /// # fn main() -> std::io::Result<()> {
+    /// let mut sp = Spinner::new(Spinners::Dots, "Checking cargo...", None);
+    /// sp.set_output_tail(5);
+    /// sp.run_command(Command::new("cargo").arg("--version"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn set_output_tail(&mut self, lines: usize) {
+        self.output_tail = lines;
+    }
+
+    /// Kill the command run by [`Spinner::run_command`], along with everything it started, if it runs longer than `timeout`.
+    ///
+    /// The spinner fails with how long it waited, and `run_command` returns an error of kind `TimedOut`.
+    /// Pass `None` to wait as long as it takes again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::process::Command;
+    /// # use std::time::Duration;
+    /// #
+    /// # #[cfg(unix)] {
+    /// let mut sp = Spinner::new(Spinners::Dots, "Waiting for the server...", None);
+    /// sp.set_command_timeout(Duration::from_millis(200));
+    /// let err = sp.run_command(Command::new("sleep").arg("5")).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    /// # }
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// * On Unix, the command runs in a process group of its own so it can be killed as a whole.
+    ///   Signals from the terminal, like `Ctrl-C`, are only passed on to it by `install_signal_handlers`.
+    /// * On Unix, the command's stdin is set to null. It can't read from the terminal outside of the terminal's
+    ///   process group, and would be stopped until the timeout kills it.
+    ///
+    pub fn set_command_timeout<T: Into<Option<Duration>>>(&mut self, timeout: T) {
+        self.command_timeout = timeout.into();
+    }
+
+    /// Set the symbols used by `success`, `fail`, `warn` and `info` for this spinner.
+    ///
+    /// Spinners without a theme of their own use the one set with [`set_default_theme`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Deploying...", None);
+    /// sp.set_theme(Theme::emoji());
+    /// sleep(Duration::from_millis(800));
+    /// sp.success("Deployed!");
+    /// ```
+    ///
+    pub fn set_theme(&mut self, theme: Theme) {
+        *lock(&self.shared.theme) = Some(theme);
+    }
+
+    /// Choose which stream each kind of finish line goes to.
+    ///
+    /// By default, `fail` prints to stderr and everything else to the spinner's stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Checking...", None);
+    /// sp.set_routing(Routing::default().warn(Streams::Stderr));
+    /// sleep(Duration::from_millis(800));
+    /// sp.warn("Disk almost full"); // printed to stderr
+    /// ```
+    ///
+    pub fn set_routing(&mut self, routing: Routing) {
+        *lock(&self.shared.routing) = routing;
+    }
+
+    /// Pause the spinner: stop animating and clear the line, but keep the spinner around.
+    ///
+    /// Nothing is drawn until [`Spinner::resume`] is called, so the terminal can be used in the meantime.
+    /// Both methods take `&self`, so they can be called from other threads as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Working...", None);
+    /// sleep(Duration::from_millis(800));
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         sp.pause();
+    ///         println!("A large block of output");
+    ///         sp.resume();
+    ///     });
+    /// });
+    /// sleep(Duration::from_millis(800));
+    /// sp.success("Done!");
+    /// ```
+    ///
+    pub fn pause(&self) {
+        let _ = self.shared.pause();
+    }
+
+    /// Resume a paused spinner, with the same message, color and frame position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Working...", None);
+    /// sp.pause();
+    /// assert!(sp.is_paused());
+    /// sp.resume();
+    /// sleep(Duration::from_millis(800));
+    /// sp.success("Done!");
+    /// ```
+    ///
+    pub fn resume(&self) {
+        self.shared.resume();
+    }
+
+    /// Clear the spinner, run `f` with the terminal to itself and resume the animation afterwards.
+    ///
+    /// This is meant for interactive prompts in the middle of a long step:
+    /// the spinner continues below the prompt once the user answered it.
+    /// A spinner that was already paused stays paused.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Installing...", None);
+    /// sleep(Duration::from_millis(800));
+    /// let overwrite = sp.suspend(|| {
+    ///     println!("config.toml already exists, overwriting it.");
+    ///     true
+    /// });
+    /// assert!(overwrite);
+    /// sleep(Duration::from_millis(800));
+    /// sp.success("Installed!");
+    /// ```
+    ///
+    pub fn suspend<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let was_paused = self.is_paused();
+        self.pause();
+        let result = f();
+        if !was_paused {
+            self.resume();
         }
+        result
     }
+
+    /// Returns whether the spinner is paused.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::Relaxed)
+    }
+
+    /// Returns the time since the spinner was created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Waiting...", None);
+    /// sleep(Duration::from_millis(800));
+    /// assert!(sp.elapsed() >= Duration::from_millis(800));
+    /// sp.clear();
+    /// ```
+    ///
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Set the total amount of work, which turns the spinner into a progress bar with a percentage.
+    ///
+    /// The bar is drawn in the spinner's color, in place of the frames, and the finish methods work as usual.
+    /// It's fine to find out the length midway: progress made so far with [`Spinner::inc`] is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Listing files...", Color::Blue);
+    /// let files = vec!["a.txt", "b.txt", "c.txt"];
+    /// sp.set_length(files.len() as u64);
+    /// for _file in files {
+    ///     sleep(Duration::from_millis(300));
+    ///     sp.inc(1);
+    /// }
+    /// sp.success("Copied 3 files");
+    /// ```
+    ///
+    pub fn set_length(&self, length: u64) {
+        self.shared.progress.set_length(length, true);
+        // Switch to the bar right away rather than on the next frame.
+        self.shared.wake();
+    }
+
+    /// Set how much of the work is done, out of the length set with [`Spinner::set_length`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Downloading...", None);
+    /// sp.set_length(1024);
+    /// sp.set_position(512);
+    /// assert_eq!(sp.position(), 512);
+    /// sp.success("Downloaded");
+    /// ```
+    ///
+    pub fn set_position(&self, position: u64) {
+        self.shared.progress.set_position(position);
+    }
+
+    /// Add to how much of the work is done.
+    ///
+    /// This only updates an atomic, so it's cheap enough to call for every item, from any thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Processing...", None);
+    /// sp.inc(1);
+    /// sp.inc(2);
+    /// assert_eq!(sp.position(), 3);
+    /// sp.success("Processed");
+    /// ```
+    ///
+    pub fn inc(&self, delta: u64) {
+        self.shared.progress.inc(delta);
+    }
+
+    /// Returns how much of the work is done.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.shared.progress.position()
+    }
+
+    /// Show the position as a count after the message, along with how fast it's going, like `12,408 files (3,210/s)`.
+    ///
+    /// The count is out of the length, if one is set. Pass `None` to hide it again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Downloading", None);
+    /// sp.set_unit(Unit::Bytes);
+    /// for _chunk in 0..8 {
+    ///     sleep(Duration::from_millis(100));
+    ///     sp.inc(64 * 1024);
+    /// }
+    /// sp.success("Downloaded 512.0 KiB");
+    /// ```
+    ///
+    pub fn set_unit<U>(&mut self, unit: U)
+    where
+        U: Into<Option<Unit>>,
+    {
+        let unit = unit.into();
+        self.restart(|spinner| spinner.unit = unit);
+    }
+
+    /// Count the items of an iterator, out of `length` if it's known, for the iterator adapters.
+    pub(crate) fn count_items(&mut self, length: Option<u64>) {
+        // The total goes with the count, a bar is left to the spinners that asked for one.
+        if let Some(length) = length {
+            if self.shared.progress.length().is_none() {
+                self.shared.progress.set_length(length, false);
+            }
+        }
+        self.set_unit(Unit::items(""));
+    }
+
+    /// Returns a handle on the position, which can be moved to other threads to count from there.
+    ///
+    /// See [`Counter`] for an example.
+    #[must_use]
+    pub fn counter(&self) -> Counter {
+        Counter::new(&self.shared)
+    }
+
+    /// Wrap a reader so the bytes read through it are counted on the spinner, and shown with their rate.
+    ///
+    /// This sets the unit to [`Unit::Bytes`]. Set a length too to get a progress bar, e.g. from a `Content-Length`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::io;
+    /// #
+    /// # fn main() -> io::Result<()> {
+    /// let mut sp = Spinner::new(Spinners::Dots, "Hashing", None);
+    /// let data = vec![0u8; 64 * 1024];
+    /// let mut reader = sp.wrap_read(data.as_slice());
+    /// io::copy(&mut reader, &mut io::sink())?;
+    /// assert_eq!(sp.position(), 64 * 1024);
+    /// sp.success("Hashed 64.0 KiB");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn wrap_read<R>(&mut self, reader: R) -> CountingReader<R> {
+        self.set_unit(Unit::Bytes);
+        CountingReader::new(reader, self.counter())
+    }
+
+    /// Wrap a writer so the bytes written through it are counted on the spinner, and shown with their rate.
+    ///
+    /// This sets the unit to [`Unit::Bytes`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::io::{self, Write};
+    /// #
+    /// # fn main() -> io::Result<()> {
+    /// let mut sp = Spinner::new(Spinners::Dots, "Extracting", None);
+    /// let mut writer = sp.wrap_write(Vec::new());
+    /// writer.write_all(b"hello world")?;
+    /// assert_eq!(sp.position(), 11);
+    /// sp.success("Extracted 11 B");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn wrap_write<W>(&mut self, writer: W) -> CountingWriter<W> {
+        self.set_unit(Unit::Bytes);
+        CountingWriter::new(writer, self.counter())
+    }
+
     /// Stop the spinner.
     ///
     /// # Example
@@ -183,10 +777,30 @@ impl Spinner {
     ///
     /// * The spinner will be dropped after this method is called, the message will remain though.
     ///
-    pub fn stop(mut self) {
-        self.stop_spinner_thread();
+    pub fn stop(self) -> Duration {
+        let started = self.started;
+        self.try_stop().unwrap_or_else(|_| started.elapsed())
+    }
+
+    /// Like [`Spinner::stop`], but returns an error if the spinner thread panicked or the line couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Spinning...", None)?;
+    /// let took = sp.try_stop()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_stop(self) -> Result<Duration, Error> {
         // print message
-        writeln!(self.stream, "{}", self.msg);
+        let msg = self.text_style.apply(&self.msg);
+        let stream = self.shared.stream;
+        self.finish(msg, stream, &[])
     }
 
     /// Stops the spinner and prints a message on a new line.
@@ -203,10 +817,30 @@ impl Spinner {
     /// sp.stop_with_message("Bye");
     /// ```
     ///
-    pub fn stop_with_message(mut self, msg: &str) {
-        self.stop_spinner_thread();
+    pub fn stop_with_message<T: Display>(self, msg: T) -> Duration {
+        let started = self.started;
+        self.try_stop_with_message(msg)
+            .unwrap_or_else(|_| started.elapsed())
+    }
+
+    /// Like [`Spinner::stop_with_message`], but returns an error if the spinner thread panicked or the line couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Hello", None)?;
+    /// sp.try_stop_with_message("Bye")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_stop_with_message<T: Display>(self, msg: T) -> Result<Duration, Error> {
         // put the message over the spinner
-        writeln!(self.stream, "{}", msg);
+        let stream = self.shared.stream;
+        self.finish(msg, stream, &[])
     }
 
     /// Deletes the spinner and message and prints a new line with a symbol and message.
@@ -223,9 +857,29 @@ impl Spinner {
     /// sp.stop_and_persist("🍕", "Pizza!");
     /// ```
     ///
-    pub fn stop_and_persist(mut self, symbol: &str, msg: &str) {
-        self.stop_spinner_thread();
-        writeln!(self.stream, "{} {}", symbol, msg);
+    pub fn stop_and_persist<T: Display>(self, symbol: &str, msg: T) -> Duration {
+        let started = self.started;
+        self.try_stop_and_persist(symbol, msg)
+            .unwrap_or_else(|_| started.elapsed())
+    }
+
+    /// Like [`Spinner::stop_and_persist`], but returns an error if the spinner thread panicked or the line couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Cooking...", None)?;
+    /// sp.try_stop_and_persist("🍕", "Pizza!")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_stop_and_persist<T: Display>(self, symbol: &str, msg: T) -> Result<Duration, Error> {
+        let stream = self.shared.stream;
+        self.finish(format!("{} {}", symbol, msg), stream, &[])
     }
 
     /// Deletes the last line of the terminal and prints a success symbol with a message.
@@ -242,9 +896,31 @@ impl Spinner {
     /// sp.success("Success!");
     /// ```
     ///
-    pub fn success(mut self, msg: &str) {
-        self.stop_spinner_thread();
-        writeln!(self.stream, "{} {}", colorize(Some(Color::Green), "✓").bold(), msg);
+    /// # Notes
+    ///
+    /// * If one of the [children](Spinner::child) failed, the spinner fails instead.
+    ///
+    pub fn success<T: Display>(self, msg: T) -> Duration {
+        let started = self.started;
+        self.try_success(msg).unwrap_or_else(|_| started.elapsed())
+    }
+
+    /// Like [`Spinner::success`], but returns an error if the spinner thread panicked or the line couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Loading...", None)?;
+    /// sp.try_success("Success!")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_success<T: Display>(self, msg: T) -> Result<Duration, Error> {
+        self.finish_with_symbol(Finish::Success, msg)
     }
 
     /// Deletes the last line of the terminal and prints a failure symbol with a message to stderr.
@@ -261,9 +937,31 @@ impl Spinner {
     /// sp.fail("Code failed to compile!");
     /// ```
     ///
-    pub fn fail(mut self, msg: &str) {
-        self.stop_spinner_thread();
-        writeln!(self.stream, "{} {}", colorize(Some(Color::Red), "✗").bold(), msg);
+    /// # Notes
+    ///
+    /// * The line goes to stderr even if the spinner is printed on stdout. Use [`Spinner::set_routing`] to change this.
+    ///
+    pub fn fail<T: Display>(self, msg: T) -> Duration {
+        let started = self.started;
+        self.try_fail(msg).unwrap_or_else(|_| started.elapsed())
+    }
+
+    /// Like [`Spinner::fail`], but returns an error if the spinner thread panicked or the line couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Compiling...", None)?;
+    /// sp.try_fail("Code failed to compile!")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_fail<T: Display>(self, msg: T) -> Result<Duration, Error> {
+        self.finish_with_symbol(Finish::Fail, msg)
     }
 
     /// Deletes the last line of the terminal and prints a warning symbol with a message.
@@ -280,9 +978,27 @@ impl Spinner {
     /// sp.warn("You might want to check your internet connection...");
     /// ```
     ///
-    pub fn warn(mut self, msg: &str) {
-        self.stop_spinner_thread();
-        writeln!(self.stream, "{} {}", colorize(Some(Color::Yellow), "⚠").bold(), msg);
+    pub fn warn<T: Display>(self, msg: T) -> Duration {
+        let started = self.started;
+        self.try_warn(msg).unwrap_or_else(|_| started.elapsed())
+    }
+
+    /// Like [`Spinner::warn`], but returns an error if the spinner thread panicked or the line couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Measuring...", None)?;
+    /// sp.try_warn("Slow network")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_warn<T: Display>(self, msg: T) -> Result<Duration, Error> {
+        self.finish_with_symbol(Finish::Warn, msg)
     }
     /// Deletes the last line of the terminal and prints an info symbol with a message.
     ///
@@ -298,9 +1014,27 @@ impl Spinner {
     /// sp.info("This is an info message!");    
     /// ```
     ///
-    pub fn info(mut self, msg: &str) {
-        self.stop_spinner_thread();
-        writeln!(self.stream, "{} {}", colorize(Some(Color::Blue), "ℹ").bold(), msg);
+    pub fn info<T: Display>(self, msg: T) -> Duration {
+        let started = self.started;
+        self.try_info(msg).unwrap_or_else(|_| started.elapsed())
+    }
+
+    /// Like [`Spinner::info`], but returns an error if the spinner thread panicked or the line couldn't be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Dots, "Loading...", None)?;
+    /// sp.try_info("Nothing to do")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_info<T: Display>(self, msg: T) -> Result<Duration, Error> {
+        self.finish_with_symbol(Finish::Info, msg)
     }
 
     /// Updates the spinner.
@@ -323,14 +1057,14 @@ impl Spinner {
     ///
     pub fn update<T, U>(&mut self, spinner: Spinners, msg: T, color: U)
     where
-        T: Into<Cow<'static, str>>,
+        T: Display,
         U: Into<Option<Color>>,
     {
-        self.stop_spinner_thread();
-        let _replaced = std::mem::replace(
-            self,
-            Self::new_with_stream(spinner, msg, color, self.stream),
-        );
+        self.restart(|this| {
+            this.spinner_type = spinner;
+            this.msg = msg.to_string();
+            this.color = color.into();
+        });
     }
 
     /// Update the spinner text.
@@ -352,13 +1086,92 @@ impl Spinner {
     ///
     pub fn update_text<T>(&mut self, msg: T)
     where
-        T: Into<Cow<'static, str>>,
+        T: Display,
     {
-        self.stop_spinner_thread();
-        let _replaced = std::mem::replace(
-            self,
-            Self::new_with_stream(self.spinner_type, msg, self.color, self.stream),
-        );
+        self.restart(|spinner| spinner.msg = msg.to_string());
+    }
+
+    /// Add a child spinner for a sub-task, drawn indented under this one.
+    ///
+    /// See [`Child`] for how finished and failed children are shown.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Building workspace...", None);
+    /// let child = sp.child("Compiling core...");
+    /// sleep(Duration::from_millis(800));
+    /// child.success("Compiled core");
+    /// sp.success("Built workspace");
+    /// ```
+    ///
+    pub fn child<T: Display>(&self, msg: T) -> Child {
+        Child::new(&self.shared, None, msg)
+    }
+
+    /// Mark the current step as done and keep spinning with the text of the next one.
+    ///
+    /// The current text is printed above the spinner with a success symbol and the time the step took,
+    /// like `✓ Resolving dependencies (1.2s)`, and the returned `Duration` is that time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Resolving dependencies...", None);
+    /// sleep(Duration::from_millis(800));
+    /// sp.step("Downloading...");
+    /// sleep(Duration::from_millis(800));
+    /// sp.step("Compiling...");
+    /// sleep(Duration::from_millis(800));
+    /// sp.success("Done!");
+    /// ```
+    ///
+    pub fn step<T: Display>(&mut self, msg: T) -> Duration {
+        self.step_with_symbol(Finish::Success, msg)
+    }
+
+    /// Like [`Spinner::step`], but marks the current step as failed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Fetching the cache...", None);
+    /// sp.step_failed("Building from scratch...");
+    /// sp.success("Built");
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// * Like [`Spinner::fail`], the line goes to stderr unless [`Spinner::set_routing`] says otherwise.
+    ///
+    pub fn step_failed<T: Display>(&mut self, msg: T) -> Duration {
+        self.step_with_symbol(Finish::Fail, msg)
+    }
+
+    /// Like [`Spinner::step`], but marks the current step with a warning.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let mut sp = Spinner::new(Spinners::Dots, "Checking the lockfile...", None);
+    /// sp.step_warned("Installing...");
+    /// sp.success("Installed");
+    /// ```
+    ///
+    pub fn step_warned<T: Display>(&mut self, msg: T) -> Duration {
+        self.step_with_symbol(Finish::Warn, msg)
     }
 
     /// Deletes the last line of the terminal.
@@ -375,22 +1188,260 @@ impl Spinner {
     /// sp.clear();
     /// ```
     ///
-    pub fn clear(mut self) {
-        self.stop_spinner_thread();
+    pub fn clear(self) {
+        let _ = self.try_clear();
+    }
+
+    /// Like [`Spinner::clear`], but returns an error if the spinner thread panicked or the line couldn't be cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// # fn main() -> Result<(), spinoff::Error> {
+    /// let sp = Spinner::try_new(Spinners::Grenade, "Clearing...", None)?;
+    /// sp.try_clear()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    pub fn try_clear(mut self) -> Result<(), Error> {
+        self.stop_spinner_thread()
+    }
+
+    /// Stop the spinner thread, change some settings and start it again.
+    ///
+    /// Errors from the old thread are dropped: the spinner keeps going with the new settings.
+    fn restart<F>(&mut self, change: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let _ = self.stop_spinner_thread();
+        change(self);
+        self.start_spinner_thread()
+            .expect("error: failed to restart the spinner thread");
+    }
+
+    /// Print the current text as a finished step, then spin with the text of the next one.
+    fn step_with_symbol<T: Display>(&mut self, kind: Finish, msg: T) -> Duration {
+        let duration = self.step_started.elapsed();
+        self.restart(|spinner| {
+            let line = format!(
+                "{} {}",
+                spinner.shared.theme().symbol(kind).render(),
+                spinner.msg
+            );
+            let stream = spinner.shared.stream_for(kind);
+            // Like the other setters, a failed write doesn't keep the spinner from going on.
+            let _ = spinner.print_line(line, stream, Some(duration));
+            spinner.msg = msg.to_string();
+            spinner.step_started = Instant::now();
+        });
+        duration
+    }
+
+    /// Stop the spinner thread and print a finish line starting with the theme's symbol.
+    fn finish_with_symbol<T: Display>(self, kind: Finish, msg: T) -> Result<Duration, Error> {
+        self.finish_with_details(kind, msg, &[])
+    }
+
+    /// Like `finish_with_symbol`, with lines printed under the finish line, such as the output of a failed command.
+    pub(crate) fn finish_with_details<T: Display>(
+        self,
+        kind: Finish,
+        msg: T,
+        details: &[String],
+    ) -> Result<Duration, Error> {
+        // A failed child fails the whole spinner.
+        let kind = if kind == Finish::Success && self.shared.child_failed.load(Ordering::Relaxed) {
+            Finish::Fail
+        } else {
+            kind
+        };
+        let line = format!("{} {}", self.shared.theme().symbol(kind).render(), msg);
+        let stream = self.shared.stream_for(kind);
+        self.finish(line, stream, details)
+    }
+
+    /// Stop the spinner thread and print the finish line, appending the duration if requested.
+    /// The `details` go under it, before the failed children.
+    fn finish<T: Display>(
+        mut self,
+        line: T,
+        stream: Streams,
+        details: &[String],
+    ) -> Result<Duration, Error> {
+        self.stop_spinner_thread()?;
+        let duration = self.started.elapsed();
+        let shown = self.timer.on_finish().then_some(duration);
+        self.print_line(line, stream, shown)?;
+        for line in details {
+            self.print_line(line, stream, None)?;
+        }
+        for line in tree::failed_children(&self.shared) {
+            self.print_line(line, stream, None)?;
+        }
+        Ok(duration)
+    }
+
+    /// Print a line while the spinner thread is stopped, followed by a duration if there is one.
+    fn print_line<T: Display>(
+        &self,
+        line: T,
+        stream: Streams,
+        duration: Option<Duration>,
+    ) -> io::Result<()> {
+        // An interrupt hook already printed the last line for this spinner.
+        if self.shared.interrupted.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let mut line = line.to_string();
+        if !should_colorize(stream) {
+            line = strip_ansi(&line);
+        }
+        match duration {
+            Some(duration) => writeln!(stream, "{} ({})", line, format_duration(duration))?,
+            None => writeln!(stream, "{}", line)?,
+        }
+        stream.flush()
+    }
+
+    /// Start the thread printing the spinner with the current settings.
+    ///
+    /// The thread works on copies of the settings, so changing them means stopping the thread and starting it again.
+    fn start_spinner_thread(&mut self) -> Result<(), Error> {
+        if self.shared.interrupted.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        self.shared.still_spinning.store(true, Ordering::Relaxed);
+        let spinner_type = self.spinner_type;
+        let stream = self.shared.stream;
+        let color = self.color;
+        let background = self.background;
+        let timer = self.timer;
+        let unit = self.unit.clone();
+        let started = self.started;
+        let hide = self.hide_cursor && stream.is_terminal();
+        let colors = should_colorize(stream);
+        // Style the message once instead of on every frame.
+        let mut msg = self.text_style.apply(&self.msg);
+        if !colors {
+            msg = strip_ansi(&msg);
+        }
+        // We use atomic bools to make the thread stop itself when the `spinner.stop()` method is called.
+        let handle = thread::Builder::new().name("spinoff".into()).spawn({
+            // Clone the shared state so that we can use it in the thread and keep the original one.
+            let shared = Arc::clone(&self.shared);
+            move || -> io::Result<()> {
+                let spinner_data = SPINNER_FRAMES
+                    .get(&spinner_type)
+                    .expect("error: invalid spinner type");
+                let interval = Duration::from_millis(u64::from(spinner_data.interval));
+                let mut frames = spinner_data.frames.iter().cycle();
+                let mut rate = Rate::new(shared.progress.position());
+                // The lock is only let go of while waiting for the next frame,
+                // so `pause` and the interrupt hooks can't clear the line in the middle of one.
+                let mut guard = lock(&shared.draw_lock);
+                // Iterate over all the frames of the spinner while the atomic bool is true.
+                while shared.still_spinning.load(Ordering::Relaxed) {
+                    if shared.paused.load(Ordering::Relaxed) {
+                        // `pause` already cleared the line, wait until we're resumed or stopped.
+                        guard = shared.wait(guard, None);
+                        continue;
+                    }
+                    if !stream.is_foreground() {
+                        // The process was moved to the background. Drawing would get us stopped with `SIGTTOU`
+                        // or scribble over the shell, so wait until we're back in the foreground.
+                        guard = shared.wait(guard, Some(interval));
+                        continue;
+                    }
+                    let Some(frame) = frames.next() else {
+                        break;
+                    };
+                    if hide && !shared.cursor_hidden.swap(true, Ordering::Relaxed) {
+                        // The cursor would otherwise blink right after the message on every redraw.
+                        hide_cursor(stream)?;
+                    }
+                    let frame = paint(frame, color, background, &[], colors);
+                    // Once the length is known, the bar takes the place of the frame.
+                    let mut frame_str = match shared.progress.bar_length() {
+                        Some(length) => {
+                            let position = shared.progress.position();
+                            let bar = progress::bar(position, length, color, background, colors);
+                            format!("{} {}", bar, msg)
+                        }
+                        None => format!("{} {}", frame, msg),
+                    };
+                    if let Some(unit) = &unit {
+                        let position = shared.progress.position();
+                        let count = unit.count(position, shared.progress.length());
+                        let rate = unit.rate(rate.update(position));
+                        frame_str.push_str(&format!(" {} ({})", count, rate));
+                    }
+                    if timer.is_live() {
+                        frame_str.push_str(&format!(" ({})", format_duration(started.elapsed())));
+                    }
+                    // Children go on the lines below, indented under the spinner.
+                    let mut lines = vec![frame_str];
+                    tree::render_children(&shared, &frame, colors, &mut lines);
+                    command::render_tail(&shared, colors, &mut lines);
+                    // Dynamically delete the last lines of the terminal depending on the width of the message + spinner.
+                    shared.clear_drawn()?;
+                    shared.last_width.store(
+                        visible_width(lines.last().map_or("", String::as_str)),
+                        Ordering::Relaxed,
+                    );
+                    shared.last_height.store(lines.len() - 1, Ordering::Relaxed);
+                    write!(stream, "{}", lines.join("\n"))?;
+                    stream.flush()?;
+
+                    guard = shared.wait(guard, Some(interval));
+                }
+                // An interrupt hook already took care of the line.
+                if shared.interrupted.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                shared.clear_drawn()?;
+                if shared.cursor_hidden.swap(false, Ordering::Relaxed) {
+                    show_cursor(stream)?;
+                }
+                stream.flush()
+            }
+        })?;
+        self.thread_handle = Some(handle);
+        Ok(())
     }
 
     /// Stop the spinner thread and wait for it.
-    fn stop_spinner_thread(&mut self) {
-        // Set flag to signal thread to stop
-        self.still_spinning
-            .store(false, std::sync::atomic::Ordering::Relaxed);
+    fn stop_spinner_thread(&mut self) -> Result<(), Error> {
+        // Set flag to signal thread to stop, and wake it up in case it's waiting
+        self.shared.still_spinning.store(false, Ordering::Relaxed);
+        self.shared.wake();
 
         // Wait for the thread to actually stop
         // Also deletes the last line of the terminal after stopped
-        self.thread_handle
-            .take()
-            .expect("Stopping the spinner thread should only happen once.")
-            .join()
-            .expect("Thread to join.");
+        let Some(handle) = self.thread_handle.take() else {
+            return Ok(());
+        };
+        match handle.join() {
+            Ok(Ok(())) => Ok(()),
+            // Whoever was reading the output went away, e.g. when piped into `head`.
+            // The thread already stopped drawing, which is all there is to do.
+            Ok(Err(err)) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            Ok(Err(err)) => Err(Error::Io(err)),
+            Err(_) => Err(Error::ThreadPanicked),
+        }
+    }
+}
+
+impl Drop for Spinner {
+    /// Stop the spinner thread if the spinner wasn't finished, so the line is cleared and the cursor is shown again.
+    fn drop(&mut self) {
+        // The spinner is dropped by a panic, so it never got to finish.
+        if let Some(cleanup) = hooks::unwinding_cleanup() {
+            let _ = self.shared.interrupt(&cleanup);
+        }
+        let _ = self.stop_spinner_thread();
     }
 }
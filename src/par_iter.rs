@@ -0,0 +1,148 @@
+use crate::{Counter, Spinner, Spinners};
+use rayon::iter::plumbing::{Consumer, Folder, UnindexedConsumer};
+use rayon::iter::ParallelIterator;
+use std::fmt::Display;
+
+/// Spin while a rayon parallel iterator runs. Only available with the `rayon` feature.
+///
+/// Implemented for every parallel iterator.
+pub trait ParallelSpinnerIterator: ParallelIterator {
+    /// Show a spinner with the number of items done so far, out of the total when it's known.
+    ///
+    /// Workers count on an atomic, so there's no lock between them. The spinner finishes with `success`
+    /// once every item is done, and is cleared if a worker panics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let sum: u64 = (0..10_000u64)
+    ///     .into_par_iter()
+    ///     .spinner(Spinners::Dots, "Crunching")
+    ///     .map(|n| n * 2)
+    ///     .sum();
+    /// assert_eq!(sum, 99_990_000);
+    /// ```
+    fn spinner<T: Display>(self, spinner_type: Spinners, msg: T) -> ParSpinnerIter<Self> {
+        self.with_spinner(Spinner::new(spinner_type, msg, None))
+    }
+
+    /// Like [`ParallelSpinnerIterator::spinner`], with a spinner that's already set up.
+    ///
+    /// Set a length on the spinner with [`Spinner::set_length`] to get a progress bar instead of the frames.
+    fn with_spinner(self, mut spinner: Spinner) -> ParSpinnerIter<Self> {
+        spinner.count_items(self.opt_len().map(|length| length as u64));
+        ParSpinnerIter {
+            base: self,
+            spinner,
+        }
+    }
+}
+
+impl<I: ParallelIterator> ParallelSpinnerIterator for I {}
+
+/// A parallel iterator with a spinner, returned by [`ParallelSpinnerIterator::spinner`].
+#[derive(Debug)]
+pub struct ParSpinnerIter<I> {
+    base: I,
+    spinner: Spinner,
+}
+
+impl<I: ParallelIterator> ParallelIterator for ParSpinnerIter<I> {
+    type Item = I::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let consumer = CountingConsumer {
+            base: consumer,
+            counter: self.spinner.counter(),
+        };
+        let result = self.base.drive_unindexed(consumer);
+        let msg = self.spinner.msg.clone();
+        self.spinner.success(msg);
+        result
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.base.opt_len()
+    }
+}
+
+/// Counts every item once the rest of the chain is done with it.
+struct CountingConsumer<C> {
+    base: C,
+    counter: Counter,
+}
+
+impl<T, C: Consumer<T>> Consumer<T> for CountingConsumer<C> {
+    type Folder = CountingFolder<C::Folder>;
+    type Reducer = C::Reducer;
+    type Result = C::Result;
+
+    fn split_at(self, index: usize) -> (Self, Self, Self::Reducer) {
+        let (left, right, reducer) = self.base.split_at(index);
+        let left = CountingConsumer {
+            base: left,
+            counter: self.counter.clone(),
+        };
+        let right = CountingConsumer {
+            base: right,
+            counter: self.counter,
+        };
+        (left, right, reducer)
+    }
+
+    fn into_folder(self) -> Self::Folder {
+        CountingFolder {
+            base: self.base.into_folder(),
+            counter: self.counter,
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
+
+impl<T, C: UnindexedConsumer<T>> UnindexedConsumer<T> for CountingConsumer<C> {
+    fn split_off_left(&self) -> Self {
+        CountingConsumer {
+            base: self.base.split_off_left(),
+            counter: self.counter.clone(),
+        }
+    }
+
+    fn to_reducer(&self) -> Self::Reducer {
+        self.base.to_reducer()
+    }
+}
+
+struct CountingFolder<F> {
+    base: F,
+    counter: Counter,
+}
+
+impl<T, F: Folder<T>> Folder<T> for CountingFolder<F> {
+    type Result = F::Result;
+
+    fn consume(self, item: T) -> Self {
+        let base = self.base.consume(item);
+        self.counter.inc(1);
+        CountingFolder {
+            base,
+            counter: self.counter,
+        }
+    }
+
+    fn complete(self) -> Self::Result {
+        self.base.complete()
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
@@ -1,7 +1,13 @@
 use crate::Streams;
-use colored::{ColoredString, Colorize};
+use once_cell::sync::Lazy;
+use std::env;
+use std::io;
+use unicode_width::UnicodeWidthChar;
 
-/// Color for spinner. Supports the 8 basic colors and a custom color variant.
+/// Color for spinner. Supports the 8 basic colors, their bright variants, the 256-color palette and a custom color variant.
+///
+/// Colors are automatically downgraded to what the terminal advertises through `COLORTERM` and `TERM`,
+/// so a `TrueColor` spinner is shown with the closest 256 or 16 color on terminals that can't display it.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[non_exhaustive]
 pub enum Color {
@@ -13,45 +19,450 @@ pub enum Color {
     White,
     Black,
     Magenta,
-    TrueColor { r: u8, g: u8, b: u8 },
-}
-
-// Internal macro for coloring text with a supplied Color enum variant.
-// Why not just match? Having matching automated helps with scaling and readability.
-// TODO: This macro requires a call with all the variants listed. We could rewrite this to not require that.
-macro_rules! color {
-    (
-        $($variant:ident: $paint_method:ident,)*
-    ) => {
-        pub fn colorize(color: Option<Color>, spinner: &str) -> ColoredString {
-            match color {
-                $(
-                    Some(Color::$variant) => spinner.$paint_method(),
-                )*
-                Some(Color::TrueColor {r, g, b}) => spinner.truecolor(r, g, b),
-                None => spinner.normal()
+    BrightBlue,
+    BrightGreen,
+    BrightRed,
+    BrightYellow,
+    BrightCyan,
+    BrightWhite,
+    BrightBlack,
+    BrightMagenta,
+    /// An index into the ANSI 256-color palette.
+    Ansi256(u8),
+    TrueColor {
+        r: u8,
+        g: u8,
+        b: u8,
+    },
+}
+
+/// How many colors the terminal can display.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    // Read the color depth the terminal advertises.
+    fn from_env() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+}
+
+/// The color depth of the terminal, only looked up once.
+static COLOR_DEPTH: Lazy<ColorDepth> = Lazy::new(ColorDepth::from_env);
+
+// The 16 basic colors, in palette order, with the RGB values xterm uses for them.
+const BASIC_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+// The channel values of the 6x6x6 color cube in the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    /// Replace the color with the closest one that can be shown at the given depth.
+    fn downgrade(self, depth: ColorDepth) -> Self {
+        match (self, depth) {
+            (Color::TrueColor { r, g, b }, ColorDepth::Ansi256) => {
+                Color::Ansi256(rgb_to_ansi256(r, g, b))
+            }
+            (Color::TrueColor { r, g, b }, ColorDepth::Ansi16) => closest_basic(r, g, b),
+            (Color::Ansi256(index), ColorDepth::Ansi16) => {
+                match BASIC_COLORS.get(usize::from(index)) {
+                    Some((basic, _)) => *basic,
+                    None => {
+                        let (r, g, b) = ansi256_to_rgb(index);
+                        closest_basic(r, g, b)
+                    }
+                }
             }
+            (color, _) => color,
         }
+    }
+
+    /// The SGR parameters selecting this color as the foreground, or the background.
+    fn sgr(self, background: bool) -> String {
+        let offset = if background { 10 } else { 0 };
+        match self.downgrade(*COLOR_DEPTH) {
+            Color::Ansi256(index) => format!("{};5;{}", 38 + offset, index),
+            Color::TrueColor { r, g, b } => format!("{};2;{};{};{}", 38 + offset, r, g, b),
+            basic => {
+                let index = BASIC_COLORS
+                    .iter()
+                    .position(|(color, _)| *color == basic)
+                    .expect("error: basic color missing from the palette");
+                // 30-37 for the normal colors and 90-97 for the bright ones.
+                let code = if index < 8 { 30 + index } else { 82 + index };
+                (code + offset).to_string()
+            }
+        }
+    }
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    // The closest level of the color cube, with xterm's thresholds.
+    let level = |channel: u8| match channel {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (channel - 35) / 40,
     };
+    let (qr, qg, qb) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * qr + 6 * qg + qb;
+    let cube_rgb = (
+        CUBE_LEVELS[usize::from(qr)],
+        CUBE_LEVELS[usize::from(qg)],
+        CUBE_LEVELS[usize::from(qb)],
+    );
+    if cube_rgb == (r, g, b) {
+        return cube;
+    }
+    // The gray ramp at the end of the palette may be closer, for colors that are close to gray.
+    let average = ((u16::from(r) + u16::from(g) + u16::from(b)) / 3) as u8;
+    let step = if average > 238 {
+        23
+    } else {
+        average.saturating_sub(3) / 10
+    };
+    let gray = 8 + 10 * step;
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube_rgb) {
+        232 + step
+    } else {
+        cube
+    }
+}
+
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC_COLORS[usize::from(index)].1,
+        16..=231 => {
+            let index = usize::from(index - 16);
+            (
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[(index / 6) % 6],
+                CUBE_LEVELS[index % 6],
+            )
+        }
+        _ => {
+            let gray = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+// The squared distance between two colors.
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> i32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn closest_basic(r: u8, g: u8, b: u8) -> Color {
+    BASIC_COLORS
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .expect("error: empty palette")
+}
+
+/// Internal function for painting text with SGR escape sequences.
+/// `attributes` are the SGR codes for bold, dim, etc.
+/// When `enabled` is false the text is returned without any escape sequences, including ones it already had.
+pub fn paint(
+    text: &str,
+    fg: Option<Color>,
+    bg: Option<Color>,
+    attributes: &[u8],
+    enabled: bool,
+) -> String {
+    if !enabled {
+        return strip_ansi(text);
+    }
+    let mut codes: Vec<String> = attributes.iter().map(ToString::to_string).collect();
+    codes.extend(fg.map(|color| color.sgr(false)));
+    codes.extend(bg.map(|color| color.sgr(true)));
+    if codes.is_empty() {
+        return text.to_string();
+    }
+    format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
 }
 
 /// Internal function for deleting the last line in a terminal.
 /// This is used to clear the spinner.
-pub fn delete_last_line(clear_length: usize, stream: Streams) {
-    write!(stream, "\r");
-    for _ in 0..clear_length {
-        write!(stream, " ");
-    }
-    write!(stream, "\r");
-}
-
-color!(
-    Red: red,
-    Green: green,
-    Yellow: yellow,
-    Blue: blue,
-    Magenta: magenta,
-    Cyan: cyan,
-    White: white,
-    Black: black,
-);
+pub fn delete_last_line(clear_length: usize, stream: Streams) -> io::Result<()> {
+    write!(stream, "\r{}\r", " ".repeat(clear_length))
+}
+
+/// Internal function for deleting what a spinner drew: its last line, `clear_length` wide, and the `height` lines above it.
+/// The cursor is expected at the end of the last line, like after `delete_last_line`.
+pub fn delete_last_block(clear_length: usize, height: usize, stream: Streams) -> io::Result<()> {
+    if height == 0 {
+        return delete_last_line(clear_length, stream);
+    }
+    write!(stream, "\r\x1b[{}A\x1b[J", height)
+}
+
+/// Internal function for deleting the last `count` lines in a terminal, when the cursor is at the start of the line below them.
+/// This is used to redraw blocks of several lines, like a task list.
+pub fn delete_last_lines(count: usize, stream: Streams) -> io::Result<()> {
+    if count == 0 {
+        return Ok(());
+    }
+    write!(stream, "\x1b[{}A\r\x1b[J", count)
+}
+
+/// Internal function for hiding the terminal cursor (DECTCEM).
+pub fn hide_cursor(stream: Streams) -> io::Result<()> {
+    write!(stream, "\x1b[?25l")
+}
+
+/// Internal function for showing the terminal cursor again.
+pub fn show_cursor(stream: Streams) -> io::Result<()> {
+    write!(stream, "\x1b[?25h")
+}
+
+/// Internal function for removing ANSI escape sequences (colors, styles, hyperlinks) from a string.
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in `@`..=`~`.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ST (`ESC \`).
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    stripped
+}
+
+/// Internal function for cutting text without escape sequences down to `width` columns.
+pub fn truncate(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+/// Internal function for measuring how many columns a string takes up in the terminal.
+/// Escape sequences are skipped, so already-styled text isn't over-counted.
+pub fn visible_width(text: &str) -> usize {
+    strip_ansi(text)
+        .chars()
+        .map(|c| c.width().unwrap_or(0))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_mode::env_preference;
+
+    #[test]
+    fn cube_colors_map_to_their_index() {
+        assert_eq!(rgb_to_ansi256(95, 135, 175), 67);
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+        assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
+    }
+
+    #[test]
+    fn grays_map_to_the_ramp() {
+        assert_eq!(rgb_to_ansi256(8, 8, 8), 232);
+        assert_eq!(rgb_to_ansi256(238, 238, 238), 255);
+        assert_eq!(rgb_to_ansi256(128, 128, 128), 244);
+    }
+
+    #[test]
+    fn palette_round_trips() {
+        for index in 16..=255 {
+            let (r, g, b) = ansi256_to_rgb(index);
+            assert_eq!(rgb_to_ansi256(r, g, b), index, "index {}", index);
+        }
+    }
+
+    #[test]
+    fn ansi256_to_rgb_matches_xterm() {
+        assert_eq!(ansi256_to_rgb(1), (205, 0, 0));
+        assert_eq!(ansi256_to_rgb(67), (95, 135, 175));
+        assert_eq!(ansi256_to_rgb(232), (8, 8, 8));
+        assert_eq!(ansi256_to_rgb(255), (238, 238, 238));
+    }
+
+    #[test]
+    fn downgrade() {
+        let color = Color::TrueColor {
+            r: 95,
+            g: 135,
+            b: 175,
+        };
+        assert_eq!(color.downgrade(ColorDepth::TrueColor), color);
+        assert_eq!(color.downgrade(ColorDepth::Ansi256), Color::Ansi256(67));
+        assert_eq!(
+            Color::TrueColor {
+                r: 250,
+                g: 10,
+                b: 5
+            }
+            .downgrade(ColorDepth::Ansi16),
+            Color::BrightRed
+        );
+        assert_eq!(Color::Ansi256(4).downgrade(ColorDepth::Ansi16), Color::Blue);
+        assert_eq!(
+            Color::Ansi256(46).downgrade(ColorDepth::Ansi16),
+            Color::BrightGreen
+        );
+        assert_eq!(Color::Cyan.downgrade(ColorDepth::Ansi16), Color::Cyan);
+    }
+
+    #[test]
+    fn strip_ansi_removes_sgr() {
+        assert_eq!(strip_ansi("\x1b[1;31m✗\x1b[0m Failed"), "✗ Failed");
+        assert_eq!(strip_ansi("\x1b[38;5;67mblue\x1b[39m"), "blue");
+    }
+
+    #[test]
+    fn strip_ansi_removes_csi_with_parameters() {
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1A\x1b[?25lline"), "line");
+        assert_eq!(strip_ansi("\x1b[38;2;95;135;175mrgb"), "rgb");
+    }
+
+    #[test]
+    fn strip_ansi_removes_hyperlinks() {
+        let bel = "\x1b]8;;https://example.com\x07link\x1b]8;;\x07";
+        let st = "\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\";
+        assert_eq!(strip_ansi(bel), "link");
+        assert_eq!(strip_ansi(st), "link");
+        assert_eq!(visible_width(bel), 4);
+        assert_eq!(visible_width(st), 4);
+    }
+
+    #[test]
+    fn visible_width_skips_escape_codes() {
+        assert_eq!(visible_width("\x1b[1;32m✓\x1b[0m Done"), 6);
+        assert_eq!(visible_width(""), 0);
+    }
+
+    #[test]
+    fn visible_width_counts_wide_characters() {
+        assert_eq!(visible_width("日本語"), 6);
+        assert_eq!(visible_width("\x1b[2m日本\x1b[0m ok"), 7);
+    }
+
+    #[test]
+    fn visible_width_of_colored_string() {
+        use colored::Colorize;
+
+        colored::control::set_override(true);
+        let text = "Compiled".green().bold().to_string();
+        assert_ne!(text, "Compiled");
+        assert_eq!(visible_width(&text), 8);
+        assert_eq!(strip_ansi(&text), "Compiled");
+    }
+
+    #[test]
+    fn truncate_to_columns() {
+        assert_eq!(truncate("hello", 10), "hello");
+        assert_eq!(truncate("hello", 3), "hel");
+        assert_eq!(truncate("hello", 0), "");
+        // A wide character that doesn't fit whole is left out.
+        assert_eq!(truncate("日本語", 3), "日");
+        assert_eq!(truncate("日本語", 4), "日本");
+    }
+
+    /// Internal function for looking variables up in a fixed list instead of the environment.
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value).to_owned())
+        }
+    }
+
+    #[test]
+    fn env_preference_without_variables() {
+        assert_eq!(env_preference(env(&[])), None);
+        assert_eq!(env_preference(env(&[("NO_COLOR", "")])), None);
+        assert_eq!(env_preference(env(&[("CLICOLOR", "1")])), None);
+    }
+
+    #[test]
+    fn env_preference_force_color() {
+        assert_eq!(env_preference(env(&[("FORCE_COLOR", "1")])), Some(true));
+        assert_eq!(env_preference(env(&[("FORCE_COLOR", "true")])), Some(true));
+        assert_eq!(env_preference(env(&[("FORCE_COLOR", "0")])), Some(false));
+        assert_eq!(
+            env_preference(env(&[("FORCE_COLOR", "false")])),
+            Some(false)
+        );
+        assert_eq!(env_preference(env(&[("CLICOLOR_FORCE", "1")])), Some(true));
+        assert_eq!(env_preference(env(&[("CLICOLOR_FORCE", "0")])), None);
+    }
+
+    #[test]
+    fn env_preference_no_color() {
+        assert_eq!(env_preference(env(&[("NO_COLOR", "1")])), Some(false));
+        assert_eq!(env_preference(env(&[("CLICOLOR", "0")])), Some(false));
+    }
+
+    #[test]
+    fn env_preference_precedence() {
+        let forced = [("FORCE_COLOR", "1"), ("NO_COLOR", "1"), ("CLICOLOR", "0")];
+        assert_eq!(env_preference(env(&forced)), Some(true));
+        let forced = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")];
+        assert_eq!(env_preference(env(&forced)), Some(true));
+        let disabled = [("FORCE_COLOR", "0"), ("CLICOLOR_FORCE", "1")];
+        assert_eq!(env_preference(env(&disabled)), Some(false));
+        let no_color = [("NO_COLOR", "1"), ("CLICOLOR", "1")];
+        assert_eq!(env_preference(env(&no_color)), Some(false));
+    }
+}
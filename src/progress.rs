@@ -0,0 +1,363 @@
+use crate::printer::paint;
+use crate::shared::Shared;
+use crate::Color;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// The length used while the total isn't known.
+const NO_LENGTH: u64 = u64::MAX;
+
+/// How many columns the bar takes up, without the percentage.
+const BAR_WIDTH: u64 = 20;
+
+/// The position and length of a spinner, updated from any thread and read by the spinner thread on every frame.
+#[derive(Debug)]
+pub struct Progress {
+    position: AtomicU64,
+    length: AtomicU64,
+    /// Whether the length is shown as a bar, or only as the total of the count.
+    bar: AtomicBool,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self {
+            position: AtomicU64::new(0),
+            length: AtomicU64::new(NO_LENGTH),
+            bar: AtomicBool::new(false),
+        }
+    }
+
+    // Returns the length, if it was set.
+    pub fn length(&self) -> Option<u64> {
+        Some(self.length.load(Ordering::Relaxed)).filter(|&length| length != NO_LENGTH)
+    }
+
+    // Returns the length, if it was set to be shown as a bar.
+    pub fn bar_length(&self) -> Option<u64> {
+        self.length().filter(|_| self.bar.load(Ordering::Relaxed))
+    }
+
+    pub fn set_length(&self, length: u64, bar: bool) {
+        self.bar.store(bar, Ordering::Relaxed);
+        self.length.store(length, Ordering::Relaxed);
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    pub fn set_position(&self, position: u64) {
+        self.position.store(position, Ordering::Relaxed);
+    }
+
+    pub fn inc(&self, delta: u64) {
+        self.position.fetch_add(delta, Ordering::Relaxed);
+    }
+}
+
+/// Internal function for drawing a progress bar in the spinner's colors, followed by the percentage.
+pub fn bar(
+    position: u64,
+    length: u64,
+    color: Option<Color>,
+    background: Option<Color>,
+    colors: bool,
+) -> String {
+    // An empty job is a finished one.
+    let (done, total) = if length == 0 {
+        (1, 1)
+    } else {
+        (u128::from(position.min(length)), u128::from(length))
+    };
+    // Both fit in a `usize`, as they're at most `BAR_WIDTH` and 100.
+    let filled = (done * u128::from(BAR_WIDTH) / total) as usize;
+    let percent = done * 100 / total;
+    format!(
+        "{}{} {:>3}%",
+        paint(&"█".repeat(filled), color, background, &[], colors),
+        paint(
+            &"░".repeat(BAR_WIDTH as usize - filled),
+            None,
+            None,
+            &[2],
+            colors
+        ),
+        percent
+    )
+}
+
+/// What the position of a spinner counts, to show it next to the message along with its rate.
+///
+/// Set it with [`Spinner::set_unit`](crate::Spinner::set_unit). If a length is set, it's shown as the total.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// let mut sp = Spinner::new(Spinners::Dots, "Indexing", None);
+/// sp.set_unit(Unit::items("files")); // ⠙ Indexing 12,408 files (3,210/s)
+/// sp.inc(12_408);
+/// sp.success("Indexed");
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Unit {
+    /// Things counted one by one, like `12,408 files (3,210/s)`.
+    Items(Cow<'static, str>),
+    /// Bytes, shown in binary units like `1.2 MiB (340.0 KiB/s)`.
+    Bytes,
+}
+
+impl Unit {
+    /// Count items, followed by `label`. Use an empty label for a bare number.
+    #[must_use]
+    pub fn items<T>(label: T) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        Unit::Items(label.into())
+    }
+
+    // Returns the count, out of the total if there is one, in this unit.
+    pub(crate) fn count(&self, position: u64, length: Option<u64>) -> String {
+        let mut count = self.amount(position);
+        if let Some(length) = length {
+            count = format!("{}/{}", count, self.amount(length));
+        }
+        match self {
+            Unit::Items(label) if !label.is_empty() => format!("{} {}", count, label),
+            Unit::Items(_) | Unit::Bytes => count,
+        }
+    }
+
+    // Returns the rate per second in this unit.
+    pub(crate) fn rate(&self, rate: f64) -> String {
+        match self {
+            Unit::Items(_) if rate < 10.0 => format!("{:.1}/s", rate),
+            // Rounded, and saturating for absurd rates.
+            Unit::Items(_) => format!("{}/s", thousands(rate.round() as u64)),
+            Unit::Bytes => format!("{}/s", bytes(rate)),
+        }
+    }
+
+    fn amount(&self, amount: u64) -> String {
+        match self {
+            Unit::Items(_) => thousands(amount),
+            Unit::Bytes => bytes(amount as f64),
+        }
+    }
+}
+
+/// Internal function for formatting a number with thousands separators, like `12,408`.
+fn thousands(number: u64) -> String {
+    let digits = number.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Internal function for formatting an amount of bytes in binary units, like `1.2 MiB`.
+fn bytes(amount: f64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    // Compared as rounded, so nothing is shown as `1024 B` or `1024.0 KiB`.
+    if amount.round() < 1024.0 {
+        return format!("{} B", amount.round());
+    }
+    let mut amount = amount / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if (amount * 10.0).round() < 10240.0 {
+            break;
+        }
+        amount /= 1024.0;
+        unit = next;
+    }
+    format!("{:.1} {}", amount, unit)
+}
+
+/// How quickly the rate follows changes, in seconds. Higher is smoother.
+const RATE_SMOOTHING: f64 = 1.0;
+
+/// A rate of progress, smoothed over the last second or so so it doesn't jump around on every frame.
+#[derive(Debug)]
+pub struct Rate {
+    last: Instant,
+    last_position: u64,
+    rate: Option<f64>,
+}
+
+impl Rate {
+    pub fn new(position: u64) -> Self {
+        Self {
+            last: Instant::now(),
+            last_position: position,
+            rate: None,
+        }
+    }
+
+    /// Take in the current position, and return the smoothed rate per second.
+    pub fn update(&mut self, position: u64) -> f64 {
+        self.update_at(position, Instant::now())
+    }
+
+    /// Take in the position at `now`.
+    fn update_at(&mut self, position: u64, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        if elapsed <= 0.0 {
+            return self.rate.unwrap_or(0.0);
+        }
+        // A position that went back was reset, which isn't negative progress.
+        let current = position.saturating_sub(self.last_position) as f64 / elapsed;
+        // An exponential moving average, weighted by how long it has been since the last update.
+        let weight = 1.0 - (-elapsed / RATE_SMOOTHING).exp();
+        let rate = match self.rate {
+            Some(rate) => rate + (current - rate) * weight,
+            None => current,
+        };
+        self.last = now;
+        self.last_position = position;
+        self.rate = Some(rate);
+        rate
+    }
+}
+
+/// A handle on the position of a spinner, for workers on other threads.
+///
+/// Counting only updates an atomic, so it's cheap enough to do for every item.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// # use std::thread;
+/// #
+/// let mut sp = Spinner::new(Spinners::Dots, "Indexing", None);
+/// sp.set_unit(Unit::items("files"));
+/// let workers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let counter = sp.counter();
+///         thread::spawn(move || (0..100).for_each(|_| counter.inc(1)))
+///     })
+///     .collect();
+/// workers.into_iter().for_each(|worker| worker.join().unwrap());
+/// assert_eq!(sp.position(), 400);
+/// sp.success("Indexed 400 files");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Counter {
+    shared: Arc<Shared>,
+}
+
+impl Counter {
+    pub(crate) fn new(shared: &Arc<Shared>) -> Self {
+        Self {
+            shared: Arc::clone(shared),
+        }
+    }
+    /// Add to the count.
+    pub fn inc(&self, delta: u64) {
+        self.shared.progress.inc(delta);
+    }
+    /// Set the count.
+    pub fn set(&self, position: u64) {
+        self.shared.progress.set_position(position);
+    }
+    /// Returns the count.
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.shared.progress.position()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn thousands_separators() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(1000), "1,000");
+        assert_eq!(thousands(12408), "12,408");
+        assert_eq!(thousands(1_234_567), "1,234,567");
+        assert_eq!(thousands(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn binary_units() {
+        assert_eq!(bytes(0.0), "0 B");
+        assert_eq!(bytes(1023.0), "1023 B");
+        assert_eq!(bytes(1023.7), "1.0 KiB");
+        assert_eq!(bytes(1024.0), "1.0 KiB");
+        assert_eq!(bytes(1536.0), "1.5 KiB");
+        assert_eq!(bytes(1024.0 * 1023.99), "1.0 MiB");
+        assert_eq!(bytes(1024.0 * 1024.0 * 1.25), "1.2 MiB");
+        assert_eq!(bytes(u64::MAX as f64), "16.0 EiB");
+    }
+
+    #[test]
+    fn count_in_units() {
+        assert_eq!(Unit::items("").count(0, None), "0");
+        assert_eq!(Unit::items("files").count(12408, None), "12,408 files");
+        assert_eq!(Unit::items("files").count(5, Some(1000)), "5/1,000 files");
+        assert_eq!(Unit::Bytes.count(1024, Some(2048)), "1.0 KiB/2.0 KiB");
+        assert_eq!(Unit::Bytes.count(512, None), "512 B");
+    }
+
+    #[test]
+    fn rate_in_units() {
+        assert_eq!(Unit::items("").rate(0.0), "0.0/s");
+        assert_eq!(Unit::items("").rate(4.0), "4.0/s");
+        assert_eq!(Unit::items("").rate(9.94), "9.9/s");
+        assert_eq!(Unit::items("").rate(10.0), "10/s");
+        assert_eq!(Unit::items("files").rate(3210.4), "3,210/s");
+        assert_eq!(
+            Unit::items("").rate(f64::INFINITY),
+            "18,446,744,073,709,551,615/s"
+        );
+        assert_eq!(Unit::Bytes.rate(340.0 * 1024.0), "340.0 KiB/s");
+    }
+
+    #[test]
+    fn rate_starts_at_the_first_measurement() {
+        let mut rate = Rate::new(0);
+        let start = rate.last;
+        assert_eq!(rate.update_at(100, start + Duration::from_secs(1)), 100.0);
+    }
+
+    #[test]
+    fn rate_is_smoothed() {
+        let mut rate = Rate::new(0);
+        let start = rate.last;
+        rate.update_at(100, start + Duration::from_secs(1));
+        // No progress for a second, weighted by 1 - e^-1.
+        let smoothed = rate.update_at(100, start + Duration::from_secs(2));
+        assert!((smoothed - 100.0 * (-1.0f64).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rate_without_time_passing() {
+        let mut rate = Rate::new(0);
+        let start = rate.last;
+        assert_eq!(rate.update_at(10, start), 0.0);
+        rate.update_at(100, start + Duration::from_secs(1));
+        assert_eq!(rate.update_at(500, start + Duration::from_secs(1)), 100.0);
+    }
+
+    #[test]
+    fn rate_after_going_back() {
+        let mut rate = Rate::new(100);
+        let start = rate.last;
+        assert_eq!(rate.update_at(50, start + Duration::from_secs(1)), 0.0);
+        assert_eq!(rate.last_position, 50);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::color_mode::should_colorize;
+use crate::hooks::Cleanup;
+use crate::printer::{delete_last_block, show_cursor, strip_ansi};
+use crate::progress::Progress;
+use crate::theme::{default_theme, Finish};
+use crate::tree::Node;
+use crate::{Routing, Streams, Theme};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::thread;
+use std::time::Duration;
+
+/// State shared between a spinner, the thread printing it and the interrupt hooks.
+#[derive(Debug)]
+pub struct Shared {
+    /// Used in the `stop` type methods to stop the thread printing the spinner.
+    pub still_spinning: AtomicBool,
+    /// Set once an interrupt hook finalized the spinner, after which nothing else is printed for it.
+    pub interrupted: AtomicBool,
+    /// Set while the spinner is paused.
+    pub paused: AtomicBool,
+    /// Set while the spinner thread has the cursor hidden.
+    pub cursor_hidden: AtomicBool,
+    /// The width of the line currently on screen, so it can be cleared from outside the thread.
+    pub last_width: AtomicUsize,
+    /// How many lines of children are on screen above the last one.
+    pub last_height: AtomicUsize,
+    /// Held while a frame is drawn, so the hooks never write in the middle of one.
+    pub draw_lock: Mutex<()>,
+    /// Wakes up the spinner thread when it's waiting for the next frame or to be resumed.
+    wakeup: Condvar,
+    pub stream: Streams,
+    pub theme: Mutex<Option<Theme>>,
+    pub routing: Mutex<Routing>,
+    /// The child spinners drawn under this one.
+    pub children: Mutex<Vec<Arc<Node>>>,
+    /// Set once a child, or one of its own children, failed.
+    pub child_failed: AtomicBool,
+    /// Turns the spinner into a progress bar once a length is set.
+    pub progress: Progress,
+    /// The last lines of output of a command, drawn under the spinner.
+    pub output_tail: Mutex<VecDeque<String>>,
+}
+
+impl Shared {
+    pub fn new(stream: Streams) -> Self {
+        Self {
+            still_spinning: AtomicBool::new(false),
+            interrupted: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            cursor_hidden: AtomicBool::new(false),
+            last_width: AtomicUsize::new(0),
+            last_height: AtomicUsize::new(0),
+            draw_lock: Mutex::new(()),
+            wakeup: Condvar::new(),
+            stream,
+            theme: Mutex::new(None),
+            routing: Mutex::new(Routing::default()),
+            children: Mutex::new(Vec::new()),
+            child_failed: AtomicBool::new(false),
+            progress: Progress::new(),
+            output_tail: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    // Returns the theme of the spinner, or the default one.
+    pub fn theme(&self) -> Theme {
+        lock(&self.theme).clone().unwrap_or_else(default_theme)
+    }
+
+    // Returns the stream a kind of finish line goes to.
+    pub fn stream_for(&self, kind: Finish) -> Streams {
+        lock(&self.routing).stream_for(kind, self.stream)
+    }
+
+    /// Let go of the draw lock until woken up, or until `timeout` passed.
+    pub fn wait<'a>(
+        &self,
+        guard: MutexGuard<'a, ()>,
+        timeout: Option<Duration>,
+    ) -> MutexGuard<'a, ()> {
+        match timeout {
+            Some(timeout) => {
+                self.wakeup
+                    .wait_timeout(guard, timeout)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+            None => self
+                .wakeup
+                .wait(guard)
+                .unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    /// Delete everything the spinner thread drew, the children included.
+    pub fn clear_drawn(&self) -> io::Result<()> {
+        delete_last_block(
+            self.last_width.swap(0, Ordering::Relaxed),
+            self.last_height.swap(0, Ordering::Relaxed),
+            self.stream,
+        )
+    }
+
+    // Wake up the spinner thread.
+    pub fn wake(&self) {
+        let _guard = lock(&self.draw_lock);
+        self.wakeup.notify_all();
+    }
+
+    /// Stop drawing and clear the line, until `resume` is called.
+    pub fn pause(&self) -> io::Result<()> {
+        let _guard = lock(&self.draw_lock);
+        if self.paused.swap(true, Ordering::Relaxed) {
+            return Ok(());
+        }
+        self.clear_drawn()?;
+        if self.cursor_hidden.swap(false, Ordering::Relaxed) {
+            show_cursor(self.stream)?;
+        }
+        self.stream.flush()
+    }
+
+    /// Start drawing again after `pause`.
+    pub fn resume(&self) {
+        let _guard = lock(&self.draw_lock);
+        self.paused.store(false, Ordering::Relaxed);
+        self.wakeup.notify_all();
+    }
+
+    /// Take the draw lock from a hook.
+    /// The hook may run on the thread that panicked while drawing, so don't wait for the lock forever.
+    fn hook_lock(&self) -> Option<MutexGuard<'_, ()>> {
+        (0..100).find_map(|_| match self.draw_lock.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+            Err(TryLockError::WouldBlock) => {
+                thread::sleep(Duration::from_millis(1));
+                None
+            }
+        })
+    }
+
+    /// Clear a running spinner and show the cursor from the panic hook, so the panic message gets a clean line.
+    ///
+    /// Returns whether it was cleared, in which case it's paused until `resume` is called.
+    pub fn hold(&self) -> io::Result<bool> {
+        let _guard = self.hook_lock();
+        if !self.still_spinning.load(Ordering::Relaxed) || self.paused.swap(true, Ordering::Relaxed)
+        {
+            return Ok(false);
+        }
+        self.clear_drawn()?;
+        if self.cursor_hidden.swap(false, Ordering::Relaxed) {
+            show_cursor(self.stream)?;
+        }
+        self.stream.flush()?;
+        Ok(true)
+    }
+
+    /// Finalize a running spinner from an interrupt hook: clear it, or replace it with a failure line.
+    ///
+    /// Spinners that aren't spinning at the moment are left alone.
+    pub fn interrupt(&self, cleanup: &Cleanup) -> io::Result<()> {
+        let _guard = self.hook_lock();
+        if !self.still_spinning.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        self.interrupted.store(true, Ordering::Relaxed);
+        self.wakeup.notify_all();
+        self.clear_drawn()?;
+        if self.cursor_hidden.swap(false, Ordering::Relaxed) {
+            show_cursor(self.stream)?;
+        }
+        // The failure line may go to another stream, so the line has to be cleared before it's written.
+        self.stream.flush()?;
+        if let Cleanup::Fail(msg) = cleanup {
+            let stream = self.stream_for(Finish::Fail);
+            let mut line = format!("{} {}", self.theme().symbol(Finish::Fail).render(), msg);
+            if !should_colorize(stream) {
+                line = strip_ansi(&line);
+            }
+            writeln!(stream, "{}", line)?;
+            stream.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Internal function for locking a mutex, ignoring poisoning.
+/// A panic elsewhere shouldn't keep a spinner from finishing.
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
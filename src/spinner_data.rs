@@ -2,6 +2,7 @@ use crate::spinner_enum::Spinners;
 use maplit::{self, hashmap};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// A Struct that contains the data for a spinner.
 /// Frames is a Vec of &str, each &str is a frame of the spinner.
@@ -1195,3 +1196,29 @@ pub static SPINNER_FRAMES: Lazy<HashMap<Spinners, SpinnerFrames>> = Lazy::new(||
           ], interval: 50},
     }
 });
+
+impl Spinners {
+    /// The frames of the spinner, in the order they're shown.
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// assert_eq!(Spinners::Line.frames(), ["-", "\\", "|", "/"]);
+    /// ```
+    #[must_use]
+    pub fn frames(self) -> &'static [&'static str] {
+        &SPINNER_FRAMES[&self].frames
+    }
+    /// How long each frame of the spinner is shown for.
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// # use std::time::Duration;
+    /// #
+    /// assert_eq!(Spinners::Dots.interval(), Duration::from_millis(80));
+    /// ```
+    #[must_use]
+    pub fn interval(self) -> Duration {
+        Duration::from_millis(u64::from(SPINNER_FRAMES[&self].interval))
+    }
+}
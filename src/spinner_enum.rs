@@ -3,12 +3,14 @@ use strum::EnumIter;
 /// An enum of all the available spinners.
 ///
 /// Contains around 80+ spinners.
-/// It is recommended to use the `all_spinners` example to view each spinner's animation.
+/// To see them all animated at once, run the gallery of the `spinoff` binary:
 ///
 /// ```ignore
-/// cargo run --example all_spinners
+/// cargo run --features cli -- gallery
 /// ```
 ///
+/// [`Spinners::frames`] and [`Spinners::interval`] give the data behind each one.
+///
 /// Each variant in this enum is assigned to a `HashMap` holding it's frames and interval count.
 ///
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, EnumIter, strum::Display)]
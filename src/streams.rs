@@ -1,7 +1,8 @@
-use std::io::{stderr, stdout, Write};
+use crate::theme::Finish;
+use std::io::{self, stderr, stdout, IsTerminal, Write};
 /// Simplified type for a stream.
 /// By default, `spinoff` uses `Streams::Stdout`.
-#[derive(Default, Copy, Clone, Debug)]
+#[derive(Default, Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Streams {
     #[default]
     Stdout,
@@ -17,11 +18,138 @@ impl Streams {
             Streams::Stderr => Box::new(stderr()),
         }
     }
+    // Returns whether the stream is attached to a terminal.
+    pub(crate) fn is_terminal(self) -> bool {
+        match self {
+            Streams::Stdout => stdout().is_terminal(),
+            Streams::Stderr => stderr().is_terminal(),
+        }
+    }
+    // Returns whether the process is in the foreground of the terminal the stream is attached to.
+    // Streams that aren't attached to a terminal count as being in the foreground.
+    #[cfg(unix)]
+    pub(crate) fn is_foreground(self) -> bool {
+        let fd = match self {
+            Streams::Stdout => libc::STDOUT_FILENO,
+            Streams::Stderr => libc::STDERR_FILENO,
+        };
+        // SAFETY: both calls only read the process state, and have no memory safety requirements.
+        let group = unsafe { libc::tcgetpgrp(fd) };
+        group == -1 || group == unsafe { libc::getpgrp() }
+    }
+    #[cfg(not(unix))]
+    pub(crate) fn is_foreground(self) -> bool {
+        true
+    }
+    // Returns the width of the terminal the stream is attached to, if it is attached to one.
+    pub(crate) fn width(self) -> Option<usize> {
+        self.size().map(|(columns, _)| columns)
+    }
+    // Returns the height of the terminal the stream is attached to, if it is attached to one.
+    pub(crate) fn height(self) -> Option<usize> {
+        self.size().map(|(_, rows)| rows)
+    }
+    // Returns the columns and rows of the terminal the stream is attached to.
+    #[cfg(unix)]
+    fn size(self) -> Option<(usize, usize)> {
+        let fd = match self {
+            Streams::Stdout => libc::STDOUT_FILENO,
+            Streams::Stderr => libc::STDERR_FILENO,
+        };
+        // SAFETY: `TIOCGWINSZ` only writes a `winsize` to the pointer it's given, which points to one.
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        let ok = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } == 0;
+        (ok && size.ws_col > 0 && size.ws_row > 0)
+            .then_some((usize::from(size.ws_col), usize::from(size.ws_row)))
+    }
+    #[cfg(not(unix))]
+    fn size(self) -> Option<(usize, usize)> {
+        None
+    }
     // Clever implementation that allows us to automatically get the stream when `write!` is called.
-    pub fn write_fmt<T>(self, fmt: T)
+    pub fn write_fmt<T>(self, fmt: T) -> io::Result<()>
     where
         T: std::fmt::Display,
     {
-        write!(self.get_stream(), "{}", fmt).expect("error: failed to write to stream");
+        write!(self.get_stream(), "{}", fmt)
+    }
+    // Flush the stream.
+    pub(crate) fn flush(self) -> io::Result<()> {
+        self.get_stream().flush()
+    }
+}
+
+/// Which stream each kind of finish line goes to.
+///
+/// Kinds without a stream of their own go to the stream the spinner is printed on.
+/// By default, failure lines go to `Streams::Stderr`, so they don't end up in captured stdout.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// // Send warnings to stderr as well.
+/// let routing = Routing::default().warn(Streams::Stderr);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Routing {
+    success: Option<Streams>,
+    fail: Option<Streams>,
+    warn: Option<Streams>,
+    info: Option<Streams>,
+}
+
+impl Default for Routing {
+    fn default() -> Self {
+        Self::same_stream().fail(Streams::Stderr)
+    }
+}
+
+impl Routing {
+    /// Print every finish line on the stream the spinner is printed on.
+    #[must_use]
+    pub const fn same_stream() -> Self {
+        Self {
+            success: None,
+            fail: None,
+            warn: None,
+            info: None,
+        }
+    }
+    /// Set the stream `success` prints to.
+    #[must_use]
+    pub const fn success(mut self, stream: Streams) -> Self {
+        self.success = Some(stream);
+        self
+    }
+    /// Set the stream `fail` prints to.
+    #[must_use]
+    pub const fn fail(mut self, stream: Streams) -> Self {
+        self.fail = Some(stream);
+        self
+    }
+    /// Set the stream `warn` prints to.
+    #[must_use]
+    pub const fn warn(mut self, stream: Streams) -> Self {
+        self.warn = Some(stream);
+        self
+    }
+    /// Set the stream `info` prints to.
+    #[must_use]
+    pub const fn info(mut self, stream: Streams) -> Self {
+        self.info = Some(stream);
+        self
+    }
+
+    // Returns the stream a kind of finish line goes to, given the stream of the spinner.
+    pub(crate) fn stream_for(self, kind: Finish, spinner_stream: Streams) -> Streams {
+        let stream = match kind {
+            Finish::Success => self.success,
+            Finish::Fail => self.fail,
+            Finish::Warn => self.warn,
+            Finish::Info => self.info,
+        };
+        stream.unwrap_or(spinner_stream)
     }
 }
@@ -0,0 +1,100 @@
+use crate::printer::paint;
+use crate::Color;
+
+/// Style for the message text of a spinner.
+///
+/// The spinner frame is colored through the `color` option, the style only applies to the message.
+/// By default, no styling is applied.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// let style = Style::new().fg(Color::Cyan).bold().underline();
+/// ```
+#[derive(Default, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Style {
+    /// Create a style that leaves the text as-is.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bg: None,
+            bold: false,
+            dimmed: false,
+            italic: false,
+            underline: false,
+        }
+    }
+    /// Set the foreground color.
+    #[must_use]
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+    /// Set the background color.
+    #[must_use]
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+    /// Make the text bold.
+    #[must_use]
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+    /// Make the text dimmed.
+    #[must_use]
+    pub const fn dimmed(mut self) -> Self {
+        self.dimmed = true;
+        self
+    }
+    /// Make the text italic.
+    #[must_use]
+    pub const fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+    /// Underline the text.
+    #[must_use]
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Apply the style to a piece of text.
+    ///
+    /// Escape sequences are always emitted, regardless of the [`ColorMode`](crate::ColorMode).
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// assert_eq!(Style::new().fg(Color::BrightRed).bold().apply("hi"), "\x1b[1;91mhi\x1b[0m");
+    /// ```
+    #[must_use]
+    pub fn apply(self, text: &str) -> String {
+        let attributes = [
+            (self.bold, 1),
+            (self.dimmed, 2),
+            (self.italic, 3),
+            (self.underline, 4),
+        ];
+        let codes: Vec<u8> = attributes
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, code)| *code)
+            .collect();
+        paint(text, self.fg, self.bg, &codes, true)
+    }
+}
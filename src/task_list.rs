@@ -0,0 +1,612 @@
+use crate::color_mode::should_colorize;
+use crate::elapsed::format_duration;
+use crate::hooks;
+use crate::printer::{
+    delete_last_lines, hide_cursor, paint, show_cursor, strip_ansi, truncate, visible_width,
+};
+use crate::shared::{lock, Shared};
+use crate::spinner_data::SPINNER_FRAMES;
+use crate::theme::Finish;
+use crate::{Color, Spinners, Streams, Style, Theme};
+use std::any::Any;
+use std::fmt::{self, Display};
+use std::io;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How a task of a [`TaskList`] ended.
+///
+/// Tasks can return an `Outcome`, or `()` and `Result<(), E>` which are turned into one.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Outcome {
+    /// Shown with the success symbol.
+    Success,
+    /// Shown with the fail symbol, and the message below the task.
+    Fail(String),
+    /// Shown with the warn symbol, and the message below the task.
+    Warn(String),
+    /// Shown with the skipped symbol, and the reason below the task.
+    Skip(String),
+}
+
+impl From<()> for Outcome {
+    fn from((): ()) -> Self {
+        Outcome::Success
+    }
+}
+
+impl<E: Display> From<Result<(), E>> for Outcome {
+    fn from(result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => Outcome::Success,
+            Err(err) => Outcome::Fail(err.to_string()),
+        }
+    }
+}
+
+/// How one task of a [`TaskList`] went.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TaskReport {
+    /// The name the task was added with.
+    pub name: String,
+    pub outcome: Outcome,
+    /// How long the task ran, zero if it never started.
+    pub duration: Duration,
+}
+
+/// The outcomes and timings returned by [`TaskList::run`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Summary {
+    /// Every task, in the order they were added.
+    pub tasks: Vec<TaskReport>,
+    /// How long the whole list took.
+    pub duration: Duration,
+}
+
+impl Summary {
+    /// Returns true if no task failed.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        !self
+            .tasks
+            .iter()
+            .any(|task| matches!(task.outcome, Outcome::Fail(_)))
+    }
+}
+
+type Job<'a> = Box<dyn FnOnce() -> Outcome + Send + 'a>;
+
+/// A checklist of named tasks, all listed up front and marked as they finish.
+///
+/// ```text
+/// ✓ Install dependencies (2.1s)
+/// ⠹ Build
+/// ○ Deploy
+/// ```
+///
+/// Tasks run one after the other by default, or a few at a time with [`TaskList::concurrency`].
+/// When the output isn't a terminal, nothing is redrawn: each task gets a single line once it's finished.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// # use std::thread::sleep;
+/// # use std::time::Duration;
+/// #
+/// let summary = TaskList::new(Spinners::Dots, Color::Blue)
+///     .task("Install dependencies", || sleep(Duration::from_millis(400)))
+///     .task("Build", || -> Result<(), String> { Err("missing main.rs".into()) })
+///     .task("Deploy", || Outcome::Skip("nothing to deploy".into()))
+///     .run();
+/// assert!(!summary.is_success());
+/// ```
+pub struct TaskList<'a> {
+    spinner_type: Spinners,
+    color: Option<Color>,
+    stream: Streams,
+    theme: Option<Theme>,
+    concurrency: usize,
+    exit_on_error: bool,
+    tasks: Vec<(String, Job<'a>)>,
+}
+
+impl fmt::Debug for TaskList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskList")
+            .field("spinner_type", &self.spinner_type)
+            .field("color", &self.color)
+            .field("stream", &self.stream)
+            .field("theme", &self.theme)
+            .field("concurrency", &self.concurrency)
+            .field("exit_on_error", &self.exit_on_error)
+            .field(
+                "tasks",
+                &self.tasks.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<'a> TaskList<'a> {
+    /// Create an empty task list, with the spinner used for running tasks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let tasks = TaskList::new(Spinners::Dots, None);
+    /// ```
+    #[must_use]
+    pub fn new<U>(spinner_type: Spinners, color: U) -> Self
+    where
+        U: Into<Option<Color>>,
+    {
+        Self {
+            spinner_type,
+            color: color.into(),
+            stream: Streams::default(),
+            theme: None,
+            concurrency: 1,
+            exit_on_error: true,
+            tasks: Vec::new(),
+        }
+    }
+    /// Add a task. It can return `()`, a `Result<(), E>` or an [`Outcome`].
+    ///
+    /// A task that panics is marked as failed, with the panic message below it.
+    /// The message isn't printed anywhere else, and the panic hooks leave the list alone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// install_panic_hook(Cleanup::default());
+    /// let summary = TaskList::new(Spinners::Dots, None)
+    ///     .task("Parse the config", || {
+    ///         let _port: u16 = "eighty".parse().expect("the port is a number");
+    ///     })
+    ///     .exit_on_error(false)
+    ///     .task("Build", || ())
+    ///     .run();
+    /// assert!(matches!(
+    ///     &summary.tasks[0].outcome,
+    ///     Outcome::Fail(msg) if msg.starts_with("panicked: the port is a number")
+    /// ));
+    /// assert_eq!(summary.tasks[1].outcome, Outcome::Success);
+    /// ```
+    #[must_use]
+    pub fn task<T, F, R>(mut self, name: T, task: F) -> Self
+    where
+        T: Display,
+        F: FnOnce() -> R + Send + 'a,
+        R: Into<Outcome>,
+    {
+        self.tasks
+            .push((name.to_string(), Box::new(move || task().into())));
+        self
+    }
+    /// Run up to `limit` tasks at the same time. Defaults to 1.
+    #[must_use]
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = limit.max(1);
+        self
+    }
+    /// Whether a failed task keeps the tasks that haven't started from running, which is the default.
+    /// They're marked as skipped instead.
+    #[must_use]
+    pub fn exit_on_error(mut self, exit: bool) -> Self {
+        self.exit_on_error = exit;
+        self
+    }
+    /// The stream the list is printed on. Defaults to stdout.
+    #[must_use]
+    pub fn stream(mut self, stream: Streams) -> Self {
+        self.stream = stream;
+        self
+    }
+    /// The theme for the task symbols, instead of the default one.
+    #[must_use]
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Run the tasks, and return how each of them went.
+    ///
+    /// Like a spinner, the list is cosmetic: if it can't be printed, the tasks still run to the end.
+    pub fn run(self) -> Summary {
+        let started = Instant::now();
+        let shared = Arc::new(Shared::new(self.stream));
+        *lock(&shared.theme) = self.theme;
+        hooks::register(&shared);
+        shared.still_spinning.store(true, Ordering::Relaxed);
+
+        let (names, jobs): (Vec<_>, Vec<_>) = self.tasks.into_iter().unzip();
+        let board = Mutex::new(Board {
+            states: names.iter().map(|_| State::Pending).collect(),
+            jobs: jobs.into_iter().map(Some).collect(),
+            next: 0,
+            stopped: false,
+        });
+        let changed = Condvar::new();
+        let mut renderer = Renderer::new(&shared, &names, self.spinner_type, self.color);
+        let exit_on_error = self.exit_on_error;
+        let workers = self.concurrency.min(names.len());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| work(&board, &changed, exit_on_error));
+            }
+            // Drawing errors only mean the list stops being drawn.
+            let _ = renderer.watch(&board, &changed, started);
+        });
+
+        let mut board = board.into_inner().unwrap_or_else(PoisonError::into_inner);
+        for state in &mut board.states {
+            if matches!(state, State::Pending) {
+                *state = State::Done(
+                    Outcome::Skip("skipped after a failure".into()),
+                    Duration::ZERO,
+                );
+            }
+        }
+        let _ = renderer.finish(&board.states);
+        shared.still_spinning.store(false, Ordering::Relaxed);
+
+        let tasks = names
+            .into_iter()
+            .zip(board.states)
+            .map(|(name, state)| match state {
+                State::Done(outcome, duration) => TaskReport {
+                    name,
+                    outcome,
+                    duration,
+                },
+                State::Pending | State::Running => unreachable!("every task finished"),
+            })
+            .collect();
+        Summary {
+            tasks,
+            duration: started.elapsed(),
+        }
+    }
+}
+
+/// Where a task is at.
+enum State {
+    Pending,
+    Running,
+    Done(Outcome, Duration),
+}
+
+/// The tasks and their states, shared between the workers and the thread drawing the list.
+struct Board<'a> {
+    states: Vec<State>,
+    jobs: Vec<Option<Job<'a>>>,
+    /// The next task to start.
+    next: usize,
+    /// Set when a task failed and the ones that haven't started shouldn't.
+    stopped: bool,
+}
+
+impl Board<'_> {
+    // Returns true once nothing is running and nothing else will be started.
+    fn is_done(&self) -> bool {
+        let running = self.states.iter().any(|s| matches!(s, State::Running));
+        !running && (self.stopped || self.next == self.states.len())
+    }
+}
+
+/// Internal function for running tasks off the board until there are none left.
+fn work(board: &Mutex<Board<'_>>, changed: &Condvar, exit_on_error: bool) {
+    loop {
+        let (index, job, started) = {
+            let mut board = lock(board);
+            if board.stopped || board.next == board.states.len() {
+                return;
+            }
+            let index = board.next;
+            board.next += 1;
+            let started = Instant::now();
+            board.states[index] = State::Running;
+            let job = board.jobs[index].take().expect("error: a task ran twice");
+            (index, job, started)
+        };
+        changed.notify_all();
+        let outcome = hooks::catch_task_panic(job)
+            .unwrap_or_else(|payload| Outcome::Fail(panic_message(payload.as_ref())));
+        let mut board = lock(board);
+        if exit_on_error && matches!(outcome, Outcome::Fail(_)) {
+            board.stopped = true;
+        }
+        board.states[index] = State::Done(outcome, started.elapsed());
+        drop(board);
+        changed.notify_all();
+    }
+}
+
+/// Internal function for getting the message out of a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let msg = payload
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| payload.downcast_ref::<String>().cloned());
+    match msg {
+        Some(msg) => format!("panicked: {}", msg),
+        None => "panicked".into(),
+    }
+}
+
+/// Draws the list: redrawn as a whole on a terminal, one line per finished task everywhere else.
+struct Renderer<'r> {
+    shared: &'r Shared,
+    names: &'r [String],
+    theme: Theme,
+    frames: &'static [&'static str],
+    interval: Duration,
+    color: Option<Color>,
+    colors: bool,
+    live: bool,
+    /// How many lines the last redraw took up.
+    drawn: usize,
+    /// The finished tasks already printed when not redrawing.
+    printed: Vec<bool>,
+}
+
+impl<'r> Renderer<'r> {
+    fn new(
+        shared: &'r Shared,
+        names: &'r [String],
+        spinner_type: Spinners,
+        color: Option<Color>,
+    ) -> Self {
+        let spinner_data = SPINNER_FRAMES
+            .get(&spinner_type)
+            .expect("error: invalid spinner type");
+        Self {
+            shared,
+            names,
+            theme: shared.theme(),
+            frames: &spinner_data.frames,
+            interval: Duration::from_millis(u64::from(spinner_data.interval)),
+            color,
+            colors: should_colorize(shared.stream),
+            live: shared.stream.is_terminal(),
+            drawn: 0,
+            printed: vec![false; names.len()],
+        }
+    }
+
+    /// Draw the list every frame, and whenever a task starts or finishes, until all of them are done.
+    fn watch(
+        &mut self,
+        board: &Mutex<Board<'_>>,
+        changed: &Condvar,
+        started: Instant,
+    ) -> io::Result<()> {
+        let mut board = lock(board);
+        while !board.is_done() {
+            let elapsed = started.elapsed().as_millis() / self.interval.as_millis().max(1);
+            self.draw(&board.states, elapsed as usize)?;
+            board = changed
+                .wait_timeout(board, self.interval)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, states: &[State], tick: usize) -> io::Result<()> {
+        self.draw_with(states, tick, true)
+    }
+
+    /// Draw the list, fitted to the terminal if it's going to be redrawn.
+    fn draw_with(&mut self, states: &[State], tick: usize, fit: bool) -> io::Result<()> {
+        let stream = self.shared.stream;
+        let _guard = lock(&self.shared.draw_lock);
+        // An interrupt hook already cleared the list, or replaced it with a failure line.
+        if self.shared.interrupted.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        if self.live {
+            if !stream.is_foreground() {
+                return Ok(());
+            }
+            if !self.shared.cursor_hidden.swap(true, Ordering::Relaxed) {
+                hide_cursor(stream)?;
+            }
+            // Lines that wrap, or a list taller than the terminal, would throw off how many lines are cleared next time.
+            // Nothing is cleared after the last draw, so it's shown in full.
+            let width = if fit { stream.width() } else { None };
+            let rows: Vec<(bool, Vec<String>)> = self
+                .names
+                .iter()
+                .zip(states)
+                .map(|(name, state)| {
+                    let done = matches!(state, State::Done(..));
+                    (done, self.line(name, state, tick, width))
+                })
+                .collect();
+            let lines = match stream.height().filter(|_| fit) {
+                Some(height) => fold(rows, height.saturating_sub(1).max(2), self.colors),
+                None => rows.into_iter().flat_map(|(_, lines)| lines).collect(),
+            };
+            delete_last_lines(self.drawn, stream)?;
+            self.drawn = lines.len();
+            // The block ends with a newline, so clearing it from a hook only has to go up.
+            self.shared.last_width.store(0, Ordering::Relaxed);
+            self.shared.last_height.store(self.drawn, Ordering::Relaxed);
+            for line in lines {
+                writeln!(stream, "{}", line)?;
+            }
+        } else {
+            for (index, state) in states.iter().enumerate() {
+                if matches!(state, State::Done(..)) && !self.printed[index] {
+                    self.printed[index] = true;
+                    for line in self.line(&self.names[index], state, tick, None) {
+                        writeln!(stream, "{}", line)?;
+                    }
+                }
+            }
+        }
+        stream.flush()
+    }
+
+    /// Draw the list one last time and give the cursor back.
+    fn finish(&mut self, states: &[State]) -> io::Result<()> {
+        self.draw_with(states, 0, false)?;
+        let _guard = lock(&self.shared.draw_lock);
+        if self.shared.cursor_hidden.swap(false, Ordering::Relaxed) {
+            show_cursor(self.shared.stream)?;
+        }
+        self.shared.stream.flush()
+    }
+
+    // Returns the line for a task, followed by the lines of its message if it has one.
+    // With a `width`, every line is cut down to fit in it.
+    fn line(&self, name: &str, state: &State, tick: usize, width: Option<usize>) -> Vec<String> {
+        let dimmed = Style::new().dimmed();
+        let mut lines = match state {
+            State::Pending => {
+                let symbol = self.theme.pending_symbol().render();
+                let name = fit(name, width, visible_width(&symbol) + 1);
+                vec![format!("{} {}", symbol, dimmed.apply(name))]
+            }
+            State::Running => {
+                let frame = self.frames[tick % self.frames.len()];
+                let name = fit(name, width, visible_width(frame) + 1);
+                vec![format!(
+                    "{} {}",
+                    paint(frame, self.color, None, &[], true),
+                    name
+                )]
+            }
+            State::Done(outcome, duration) => {
+                let (symbol, detail) = match outcome {
+                    Outcome::Success => (self.theme.symbol(Finish::Success), None),
+                    Outcome::Fail(msg) => (self.theme.symbol(Finish::Fail), Some(msg)),
+                    Outcome::Warn(msg) => (self.theme.symbol(Finish::Warn), Some(msg)),
+                    Outcome::Skip(reason) => (self.theme.skipped_symbol(), Some(reason)),
+                };
+                let symbol = symbol.render();
+                let mut lines = vec![if matches!(outcome, Outcome::Skip(_)) {
+                    let name = fit(name, width, visible_width(&symbol) + 1);
+                    format!("{} {}", symbol, dimmed.apply(name))
+                } else {
+                    let duration = format!(" ({})", format_duration(*duration));
+                    let name = fit(name, width, visible_width(&symbol) + 1 + duration.len());
+                    format!("{} {}{}", symbol, name, duration)
+                }];
+                for (index, detail) in detail.into_iter().flat_map(|d| d.lines()).enumerate() {
+                    let arrow = if index == 0 { "→" } else { " " };
+                    let detail = fit(detail, width, 4);
+                    lines.push(format!(
+                        "  {}",
+                        dimmed.apply(&format!("{} {}", arrow, detail))
+                    ));
+                }
+                lines
+            }
+        };
+        if !self.colors {
+            for line in &mut lines {
+                *line = strip_ansi(line);
+            }
+        }
+        lines
+    }
+}
+
+/// Internal function for cutting text down to what's left of a line `width` wide after `taken` columns.
+fn fit(text: &str, width: Option<usize>, taken: usize) -> &str {
+    match width {
+        Some(width) => truncate(text, width.saturating_sub(taken)),
+        None => text,
+    }
+}
+
+/// Internal function for fitting the lines of every task into `height` lines.
+///
+/// The first finished tasks are folded into a `+k done` line, then the last tasks into a `+k more` line.
+fn fold(mut rows: Vec<(bool, Vec<String>)>, height: usize, colors: bool) -> Vec<String> {
+    let mut total: usize = rows.iter().map(|(_, lines)| lines.len()).sum();
+    let mut done = 0;
+    while total > height {
+        let Some(index) = rows.iter().position(|(finished, _)| *finished) else {
+            break;
+        };
+        total -= rows.remove(index).1.len();
+        // The first one folded makes room for the count.
+        if done == 0 {
+            total += 1;
+        }
+        done += 1;
+    }
+    let mut more = 0;
+    while total > height && !rows.is_empty() {
+        let Some((_, lines)) = rows.pop() else {
+            break;
+        };
+        total -= lines.len();
+        if more == 0 {
+            total += 1;
+        }
+        more += 1;
+    }
+    let dimmed = Style::new().dimmed();
+    let count = |text: String| {
+        if colors {
+            dimmed.apply(&text).to_string()
+        } else {
+            text
+        }
+    };
+    let mut lines = Vec::with_capacity(total);
+    if done > 0 {
+        lines.push(count(format!("+{} done", done)));
+    }
+    lines.extend(rows.into_iter().flat_map(|(_, lines)| lines));
+    if more > 0 {
+        lines.push(count(format!("+{} more", more)));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(done: usize, running: usize) -> Vec<(bool, Vec<String>)> {
+        (0..done + running)
+            .map(|index| (index < done, vec![format!("task {}", index)]))
+            .collect()
+    }
+
+    #[test]
+    fn fold_keeps_a_list_that_fits() {
+        assert_eq!(fold(rows(1, 2), 3, false), ["task 0", "task 1", "task 2"]);
+    }
+
+    #[test]
+    fn fold_finished_tasks_first() {
+        assert_eq!(fold(rows(3, 2), 3, false), ["+3 done", "task 3", "task 4"]);
+    }
+
+    #[test]
+    fn fold_the_last_tasks_when_finished_ones_are_not_enough() {
+        assert_eq!(fold(rows(1, 4), 3, false), ["+1 done", "task 1", "+3 more"]);
+        assert_eq!(fold(rows(0, 5), 3, false), ["task 0", "task 1", "+3 more"]);
+    }
+
+    #[test]
+    fn fold_counts_every_line_of_a_task() {
+        let mut rows = rows(1, 1);
+        rows[0].1.push("  → failed".into());
+        assert_eq!(fold(rows, 2, false), ["+1 done", "task 1"]);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{Color, Style};
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+/// A symbol printed in front of a finish line, along with its style.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) struct Symbol {
+    text: Cow<'static, str>,
+    style: Style,
+}
+
+impl Symbol {
+    /// Create a symbol from its text and style.
+    pub(crate) fn new<T>(text: T, style: Style) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+    // Paint the symbol.
+    pub(crate) fn render(&self) -> String {
+        self.style.apply(&self.text)
+    }
+}
+
+/// The symbols used by `success`, `fail`, `warn` and `info`, and for the pending and skipped tasks of a [`TaskList`](crate::TaskList).
+///
+/// A theme can be set per spinner with [`Spinner::set_theme`](crate::Spinner::set_theme),
+/// or for every spinner that doesn't have one with [`set_default_theme`].
+/// By default, `spinoff` uses `Theme::unicode()`.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// let theme = Theme::ascii().success("[done]", Style::new().fg(Color::Cyan));
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Theme {
+    success: Symbol,
+    fail: Symbol,
+    warn: Symbol,
+    info: Symbol,
+    pending: Symbol,
+    skipped: Symbol,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::unicode()
+    }
+}
+
+impl Theme {
+    /// `✓`, `✗`, `⚠` and `ℹ` in bold green, red, yellow and blue, with a dimmed `○` for pending and `↓` for skipped tasks.
+    #[must_use]
+    pub fn unicode() -> Self {
+        Self {
+            success: Symbol::new("✓", Style::new().fg(Color::Green).bold()),
+            fail: Symbol::new("✗", Style::new().fg(Color::Red).bold()),
+            warn: Symbol::new("⚠", Style::new().fg(Color::Yellow).bold()),
+            info: Symbol::new("ℹ", Style::new().fg(Color::Blue).bold()),
+            pending: Symbol::new("○", Style::new().dimmed()),
+            skipped: Symbol::new("↓", Style::new().dimmed()),
+        }
+    }
+    /// `[ok]`, `[FAIL]`, `[WARN]`, `[INFO]`, `[ ]` and `[skip]`, for legacy consoles and log scrapers.
+    #[must_use]
+    pub fn ascii() -> Self {
+        Self {
+            success: Symbol::new("[ok]", Style::new().fg(Color::Green)),
+            fail: Symbol::new("[FAIL]", Style::new().fg(Color::Red).bold()),
+            warn: Symbol::new("[WARN]", Style::new().fg(Color::Yellow)),
+            info: Symbol::new("[INFO]", Style::new().fg(Color::Blue)),
+            pending: Symbol::new("[ ]", Style::new().dimmed()),
+            skipped: Symbol::new("[skip]", Style::new().dimmed()),
+        }
+    }
+    /// `✅`, `❌`, `⚠️`, `ℹ️`, `⏳` and `⏭️`.
+    #[must_use]
+    pub fn emoji() -> Self {
+        Self {
+            success: Symbol::new("✅", Style::new()),
+            fail: Symbol::new("❌", Style::new()),
+            warn: Symbol::new("⚠️", Style::new()),
+            info: Symbol::new("ℹ️", Style::new()),
+            pending: Symbol::new("⏳", Style::new()),
+            skipped: Symbol::new("⏭️", Style::new()),
+        }
+    }
+    /// Set the symbol used by `success`.
+    #[must_use]
+    pub fn success<T>(mut self, text: T, style: Style) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.success = Symbol::new(text, style);
+        self
+    }
+    /// Set the symbol used by `fail`.
+    #[must_use]
+    pub fn fail<T>(mut self, text: T, style: Style) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.fail = Symbol::new(text, style);
+        self
+    }
+    /// Set the symbol used by `warn`.
+    #[must_use]
+    pub fn warn<T>(mut self, text: T, style: Style) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.warn = Symbol::new(text, style);
+        self
+    }
+    /// Set the symbol used by `info`.
+    #[must_use]
+    pub fn info<T>(mut self, text: T, style: Style) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.info = Symbol::new(text, style);
+        self
+    }
+    /// Set the symbol of tasks in a [`TaskList`](crate::TaskList) that haven't started yet.
+    #[must_use]
+    pub fn pending<T>(mut self, text: T, style: Style) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.pending = Symbol::new(text, style);
+        self
+    }
+    /// Set the symbol of tasks in a [`TaskList`](crate::TaskList) that were skipped.
+    #[must_use]
+    pub fn skipped<T>(mut self, text: T, style: Style) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.skipped = Symbol::new(text, style);
+        self
+    }
+
+    // Returns the symbol for a kind of finish line.
+    pub(crate) fn symbol(&self, kind: Finish) -> &Symbol {
+        match kind {
+            Finish::Success => &self.success,
+            Finish::Fail => &self.fail,
+            Finish::Warn => &self.warn,
+            Finish::Info => &self.info,
+        }
+    }
+    // Returns the symbol for tasks that haven't started yet.
+    pub(crate) fn pending_symbol(&self) -> &Symbol {
+        &self.pending
+    }
+    // Returns the symbol for skipped tasks.
+    pub(crate) fn skipped_symbol(&self) -> &Symbol {
+        &self.skipped
+    }
+}
+
+/// The kinds of finish lines a theme has a symbol for.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Finish {
+    Success,
+    Fail,
+    Warn,
+    Info,
+}
+
+/// The theme used by spinners that don't have one of their own.
+static DEFAULT_THEME: Lazy<RwLock<Theme>> = Lazy::new(|| RwLock::new(Theme::unicode()));
+
+/// Set the theme used by every spinner that doesn't have one of its own.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// #
+/// set_default_theme(Theme::ascii());
+/// let sp = Spinner::new(Spinners::Line, "Loading...", None);
+/// sp.success("Loaded"); // [ok] Loaded
+/// # set_default_theme(Theme::unicode());
+/// ```
+pub fn set_default_theme(theme: Theme) {
+    *DEFAULT_THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// Returns the theme used by spinners that don't have one of their own.
+#[must_use]
+pub fn default_theme() -> Theme {
+    DEFAULT_THEME
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
@@ -0,0 +1,285 @@
+use crate::printer::strip_ansi;
+use crate::shared::{lock, Shared};
+use crate::theme::Finish;
+use crate::{Style, Theme};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// A sub-task drawn indented under a spinner, or under another child.
+///
+/// Children are drawn by the thread of the spinner they belong to, with the same frames and color.
+/// A finished child collapses into a single line, and drops out of the output once its spinner finishes.
+/// Only the last few finished children of a spinner are shown, the older ones are folded into `+3 done`.
+///
+/// A failure is rolled up: a spinner or child with a failed child finishes with the fail symbol even
+/// when it's told to succeed, and the failed children stay on screen under it.
+///
+/// Dropping a child without finishing it removes it.
+///
+/// # Example
+///
+/// ```
+/// # use spinoff::*;
+/// # use std::thread::sleep;
+/// # use std::time::Duration;
+/// #
+/// let sp = Spinner::new(Spinners::Dots, "Building workspace...", None);
+/// for krate in ["core", "cli"] {
+///     let child = sp.child(format!("Compiling {}...", krate));
+///     sleep(Duration::from_millis(400));
+///     child.success(format!("Compiled {}", krate));
+/// }
+/// sp.success("Built workspace");
+/// ```
+#[derive(Debug)]
+pub struct Child {
+    node: Arc<Node>,
+    root: Arc<Shared>,
+}
+
+/// The state of a child, shared with the thread drawing it.
+#[derive(Debug)]
+pub struct Node {
+    msg: Mutex<String>,
+    state: Mutex<State>,
+    children: Mutex<Vec<Arc<Node>>>,
+    parent: Weak<Node>,
+    /// Set once one of the children of this one failed.
+    child_failed: AtomicBool,
+    started: Instant,
+}
+
+/// How many finished children of the same parent are shown while it runs, the older ones are folded into a count.
+/// Otherwise the block would outgrow the terminal, and couldn't be cleared anymore.
+const SHOWN_DONE: usize = 3;
+
+#[derive(Copy, Clone, Debug)]
+enum State {
+    Running,
+    Done(Finish),
+    Removed,
+}
+
+impl Node {
+    // Returns how a finished child is shown, with failures of its own children rolled up.
+    fn outcome(&self, kind: Finish) -> Finish {
+        if kind == Finish::Success && self.child_failed.load(Ordering::Relaxed) {
+            Finish::Fail
+        } else {
+            kind
+        }
+    }
+}
+
+impl Child {
+    /// Add a child under a spinner, or under `parent` if there is one.
+    pub(crate) fn new<T: Display>(root: &Arc<Shared>, parent: Option<&Arc<Node>>, msg: T) -> Self {
+        let node = Arc::new(Node {
+            msg: Mutex::new(msg.to_string()),
+            state: Mutex::new(State::Running),
+            children: Mutex::new(Vec::new()),
+            parent: parent.map_or_else(Weak::new, Arc::downgrade),
+            child_failed: AtomicBool::new(false),
+            started: Instant::now(),
+        });
+        match parent {
+            Some(parent) => lock(&parent.children).push(Arc::clone(&node)),
+            None => lock(&root.children).push(Arc::clone(&node)),
+        }
+        root.wake();
+        Self {
+            node,
+            root: Arc::clone(root),
+        }
+    }
+
+    /// Add a child under this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Building workspace...", None);
+    /// let krate = sp.child("Compiling core...");
+    /// let unit = krate.child("Codegen unit 1...");
+    /// unit.success("Codegen unit 1");
+    /// krate.success("Compiled core");
+    /// sp.success("Built workspace");
+    /// ```
+    ///
+    pub fn child<T: Display>(&self, msg: T) -> Child {
+        Child::new(&self.root, Some(&self.node), msg)
+    }
+
+    /// Update the text of the child.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Building workspace...", None);
+    /// let mut child = sp.child("Compiling core...");
+    /// child.update_text("Linking core...");
+    /// child.success("Built core");
+    /// sp.success("Built workspace");
+    /// ```
+    ///
+    pub fn update_text<T: Display>(&mut self, msg: T) {
+        *lock(&self.node.msg) = msg.to_string();
+        self.root.wake();
+    }
+
+    /// Mark the child as done with a success symbol.
+    ///
+    /// If one of its own children failed, the fail symbol is used instead.
+    pub fn success<T: Display>(self, msg: T) -> Duration {
+        self.finish(Finish::Success, msg)
+    }
+
+    /// Mark the child as failed. The failure is rolled up to its parents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use spinoff::*;
+    /// #
+    /// let sp = Spinner::new(Spinners::Dots, "Building workspace...", None);
+    /// sp.child("Compiling cli...").fail("cli failed to compile");
+    /// sp.success("Built workspace"); // ✗ Built workspace, with `✗ cli failed to compile` under it
+    /// ```
+    ///
+    pub fn fail<T: Display>(self, msg: T) -> Duration {
+        self.finish(Finish::Fail, msg)
+    }
+
+    /// Mark the child as done with a warning symbol.
+    pub fn warn<T: Display>(self, msg: T) -> Duration {
+        self.finish(Finish::Warn, msg)
+    }
+
+    /// Mark the child as done with an info symbol.
+    pub fn info<T: Display>(self, msg: T) -> Duration {
+        self.finish(Finish::Info, msg)
+    }
+
+    fn finish<T: Display>(self, kind: Finish, msg: T) -> Duration {
+        let duration = self.node.started.elapsed();
+        *lock(&self.node.msg) = msg.to_string();
+        *lock(&self.node.state) = State::Done(kind);
+        if self.node.outcome(kind) == Finish::Fail {
+            let mut parent = self.node.parent.upgrade();
+            while let Some(node) = parent {
+                node.child_failed.store(true, Ordering::Relaxed);
+                parent = node.parent.upgrade();
+            }
+            self.root.child_failed.store(true, Ordering::Relaxed);
+        }
+        duration
+    }
+}
+
+impl Drop for Child {
+    fn drop(&mut self) {
+        let mut state = lock(&self.node.state);
+        if let State::Running = *state {
+            *state = State::Removed;
+        }
+        drop(state);
+        self.root.wake();
+    }
+}
+
+/// Internal function for adding the lines of the children of a spinner to `lines`.
+/// `frame` is the already painted frame running children are drawn with.
+pub fn render_children(shared: &Shared, frame: &str, colors: bool, lines: &mut Vec<String>) {
+    let children = lock(&shared.children);
+    if children.is_empty() {
+        return;
+    }
+    let start = lines.len();
+    render(&children, 1, Some(frame), &shared.theme(), lines);
+    if !colors {
+        for line in &mut lines[start..] {
+            *line = strip_ansi(line);
+        }
+    }
+}
+
+/// Internal function for the lines of the failed children of a spinner, printed under its finish line.
+pub fn failed_children(shared: &Shared) -> Vec<String> {
+    let mut lines = Vec::new();
+    if shared.child_failed.load(Ordering::Relaxed) {
+        render(
+            &lock(&shared.children),
+            1,
+            None,
+            &shared.theme(),
+            &mut lines,
+        );
+    }
+    lines
+}
+
+/// Internal function for drawing children, `depth` levels deep.
+/// Without a frame only the failed ones are drawn, the way they're left behind once everything finished.
+fn render(
+    nodes: &[Arc<Node>],
+    depth: usize,
+    frame: Option<&str>,
+    theme: &Theme,
+    lines: &mut Vec<String>,
+) {
+    let indent = "  ".repeat(depth);
+    // Failures stay on screen, the rest of the finished children only need to be counted.
+    let folded = |node: &Arc<Node>| match *lock(&node.state) {
+        State::Done(kind) => node.outcome(kind) != Finish::Fail,
+        _ => false,
+    };
+    let mut hidden = match frame {
+        Some(_) => nodes
+            .iter()
+            .filter(|node| folded(node))
+            .count()
+            .saturating_sub(SHOWN_DONE),
+        None => 0,
+    };
+    if hidden > 0 {
+        let count = format!("+{} done", hidden);
+        lines.push(format!("{}{}", indent, Style::new().dimmed().apply(&count)));
+    }
+    for node in nodes {
+        if hidden > 0 && folded(node) {
+            hidden -= 1;
+            continue;
+        }
+        let state = *lock(&node.state);
+        let kind = match (state, frame) {
+            (State::Removed, _) => continue,
+            (State::Running, Some(frame)) => {
+                lines.push(format!("{}{} {}", indent, frame, lock(&node.msg)));
+                render(&lock(&node.children), depth + 1, Some(frame), theme, lines);
+                continue;
+            }
+            // A child that never finished can still have failed children to show.
+            (State::Running, None) => node.outcome(Finish::Success),
+            (State::Done(kind), _) => node.outcome(kind),
+        };
+        if frame.is_none() && kind != Finish::Fail {
+            continue;
+        }
+        lines.push(format!(
+            "{}{} {}",
+            indent,
+            theme.symbol(kind).render(),
+            lock(&node.msg)
+        ));
+        // Finished children collapse into their own line, unless there's a failure to show under it.
+        if kind == Finish::Fail {
+            render(&lock(&node.children), depth + 1, None, theme, lines);
+        }
+    }
+}
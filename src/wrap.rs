@@ -0,0 +1,151 @@
+use crate::Counter;
+use std::io::{self, BufRead, IoSlice, IoSliceMut, Read, Write};
+
+/// A reader that counts the bytes read through it on a spinner.
+///
+/// Returned by [`Spinner::wrap_read`](crate::Spinner::wrap_read).
+/// With the `tokio` feature, it's also an `AsyncRead` when the inner reader is one.
+#[derive(Debug)]
+pub struct CountingReader<R> {
+    inner: R,
+    counter: Counter,
+}
+
+/// A writer that counts the bytes written through it on a spinner.
+///
+/// Returned by [`Spinner::wrap_write`](crate::Spinner::wrap_write).
+/// With the `tokio` feature, it's also an `AsyncWrite` when the inner writer is one.
+#[derive(Debug)]
+pub struct CountingWriter<W> {
+    inner: W,
+    counter: Counter,
+}
+
+impl<R> CountingReader<R> {
+    pub(crate) fn new(inner: R, counter: Counter) -> Self {
+        Self { inner, counter }
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner reader. Bytes read through it directly aren't counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<W> CountingWriter<W> {
+    pub(crate) fn new(inner: W, counter: Counter) -> Self {
+        Self { inner, counter }
+    }
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner writer. Bytes written through it directly aren't counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.counter.inc(read as u64);
+        Ok(read)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let read = self.inner.read_vectored(bufs)?;
+        self.counter.inc(read as u64);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    // Bytes are counted once they're consumed, not when they're buffered.
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        self.counter.inc(amount as u64);
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.counter.inc(written as u64);
+        Ok(written)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let written = self.inner.write_vectored(bufs)?;
+        self.counter.inc(written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_io {
+    use super::{CountingReader, CountingWriter};
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    // The inner types have to be `Unpin` so we can get at them without unsafe pin projections.
+    // Anything else can be wrapped in a `Box::pin` first.
+
+    impl<R: AsyncRead + Unpin> AsyncRead for CountingReader<R> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let before = buf.filled().len();
+            let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+            if let Poll::Ready(Ok(())) = poll {
+                self.counter.inc((buf.filled().len() - before) as u64);
+            }
+            poll
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin> AsyncWrite for CountingWriter<W> {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+            if let Poll::Ready(Ok(written)) = poll {
+                self.counter.inc(written as u64);
+            }
+            poll
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+}